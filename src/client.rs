@@ -5,7 +5,8 @@ use songbird::serenity::SerenityInit;
 
 use crate::{
     commands::roll::RerollTable,
-    guild::{cache::GuildCacheMap, settings::GuildSettingsMap},
+    config,
+    guild::{cache::GuildCacheMap, settings::GuildSettingsMap, store},
     handlers::SerenityHandler,
     metrics,
 };
@@ -25,6 +26,9 @@ impl Client {
             .expect("Fatality! DISCORD_APP_ID not set!")
             .parse()?;
 
+        config::validate().await?;
+        store::initialize()?;
+
         let gateway_intents = GatewayIntents::non_privileged();
 
         let client = serenity::Client::builder(token, gateway_intents)
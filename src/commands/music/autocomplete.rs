@@ -0,0 +1,105 @@
+use serenity::{
+    client::Context, model::application::interaction::autocomplete::AutocompleteInteraction,
+};
+use songbird::tracks::TrackHandle;
+
+use crate::errors::ParrotError;
+
+/// Discord shows at most 25 suggestions.
+const MAX_CHOICES: usize = 25;
+
+/// Discord rejects choices with longer names.
+const MAX_CHOICE_NAME_LEN: usize = 100;
+
+/// Suggests queued tracks by title for the options that point at a track. The chosen value is
+/// the track's id rather than its position, so it still points at the same track if the queue
+/// moves before the command is sent.
+pub async fn autocomplete(
+    ctx: &Context,
+    interaction: &AutocompleteInteraction,
+) -> Result<(), ParrotError> {
+    let Some(option) = interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.focused)
+    else {
+        return Ok(());
+    };
+
+    let suggests_tracks = matches!(
+        (interaction.data.name.as_str(), option.name.as_str()),
+        ("skip", "to") | ("remove", "index" | "until") | ("move", "from")
+    );
+
+    let input = option
+        .value
+        .as_ref()
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+
+    let tracks = match (suggests_tracks, interaction.guild_id) {
+        (true, Some(guild_id)) => {
+            let manager = songbird::get(ctx).await.unwrap();
+            match manager.get(guild_id) {
+                Some(call) => call.lock().await.queue().current_queue(),
+                None => vec![],
+            }
+        }
+        _ => vec![],
+    };
+
+    let titles: Vec<Option<String>> = tracks
+        .iter()
+        .map(|track| track.metadata().title.clone())
+        .collect();
+
+    let choices = suggest_tracks(&titles, input);
+
+    interaction
+        .create_autocomplete_response(&ctx.http, |response| {
+            for (idx, name) in choices {
+                response.add_string_choice(name, tracks[idx].uuid());
+            }
+            response
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Positions and labels of the upcoming tracks whose title contains the input, or whose
+/// position starts with it.
+pub fn suggest_tracks(titles: &[Option<String>], input: &str) -> Vec<(usize, String)> {
+    let input = input.trim().to_lowercase();
+
+    titles
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(idx, title)| {
+            idx.to_string().starts_with(&input)
+                || title
+                    .as_ref()
+                    .map(|title| title.to_lowercase().contains(&input))
+                    .unwrap_or(false)
+        })
+        .take(MAX_CHOICES)
+        .map(|(idx, title)| {
+            let name = format!("{}. {}", idx, title.as_deref().unwrap_or_default());
+            (idx, name.chars().take(MAX_CHOICE_NAME_LEN).collect())
+        })
+        .collect()
+}
+
+/// Position of the track an option points at, given either as a position or, when picked
+/// from the suggestions, as the track's id.
+pub fn resolve_track(tracks: &[TrackHandle], value: &str) -> Option<usize> {
+    let value = value.trim();
+
+    value.parse::<usize>().ok().or_else(|| {
+        tracks
+            .iter()
+            .position(|track| track.uuid().to_string() == value)
+    })
+}
@@ -0,0 +1,202 @@
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    model::{
+        application::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOption,
+        },
+        channel::ChannelType,
+        id::ChannelId,
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    errors::{verify, ParrotError},
+    guild::{
+        settings::{GuildSettings, GuildSettingsMap},
+        visibility::ResponseKind,
+    },
+    messaging::locale::tr,
+    messaging::message::ParrotMusicMessage,
+    messaging::messages::{
+        CHANNELS_COMMANDS, CHANNELS_INVALID_KIND, CHANNELS_NOT_LISTED, CHANNELS_TITLE,
+        CHANNELS_VOICE, SETTINGS_ANY,
+    },
+    metrics,
+    utils::{create_embed_response_ephemeral, create_response_music_ephemeral, is_ephemeral},
+};
+
+pub async fn channels(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "channels");
+
+    let ephemeral = is_ephemeral(ctx, interaction.guild_id, ResponseKind::Settings).await;
+
+    let args = interaction.data.options.clone();
+    let Some(subcommand) = args.first() else {
+        return show_channels(ctx, interaction, ephemeral).await;
+    };
+
+    match subcommand.name.as_str() {
+        "add" => add_channel(ctx, interaction, &subcommand.options, ephemeral).await,
+        "remove" => remove_channel(ctx, interaction, &subcommand.options, ephemeral).await,
+        "clear" => clear_channels(ctx, interaction, ephemeral).await,
+        _ => show_channels(ctx, interaction, ephemeral).await,
+    }
+}
+
+async fn add_channel(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    options: &[CommandDataOption],
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+    let channel_id = get_channel_option(options)?;
+
+    let kind = interaction
+        .data
+        .resolved
+        .channels
+        .get(&channel_id)
+        .map(|channel| channel.kind);
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    let mention = channel_id.mention();
+    let message = match kind {
+        Some(ChannelType::Text | ChannelType::News) => {
+            guild_settings.add_command_channel(channel_id);
+            ParrotMusicMessage::ChannelAddedCommands { mention }
+        }
+        Some(ChannelType::Voice | ChannelType::Stage) => {
+            guild_settings.add_voice_channel(channel_id);
+            ParrotMusicMessage::ChannelAddedVoice { mention }
+        }
+        _ => return Err(ParrotError::Message(CHANNELS_INVALID_KIND)),
+    };
+    guild_settings.save()?;
+    drop(data);
+
+    create_response_music_ephemeral(&ctx.http, interaction, message, ephemeral).await
+}
+
+async fn remove_channel(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    options: &[CommandDataOption],
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+    let channel_id = get_channel_option(options)?;
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    verify(
+        guild_settings.remove_channel(channel_id),
+        ParrotError::Message(CHANNELS_NOT_LISTED),
+    )?;
+    guild_settings.save()?;
+    drop(data);
+
+    let mention = channel_id.mention();
+    create_response_music_ephemeral(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::ChannelRemoved { mention },
+        ephemeral,
+    )
+    .await
+}
+
+async fn clear_channels(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+    guild_settings.clear_channels();
+    guild_settings.save()?;
+    drop(data);
+
+    create_response_music_ephemeral(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::ChannelsCleared,
+        ephemeral,
+    )
+    .await
+}
+
+async fn show_channels(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    let mut embed = CreateEmbed::default();
+    embed.title(tr(CHANNELS_TITLE));
+    embed.field(
+        tr(CHANNELS_COMMANDS),
+        format_channels(&guild_settings.command_channels),
+        false,
+    );
+    embed.field(
+        tr(CHANNELS_VOICE),
+        format_channels(&guild_settings.voice_channels),
+        false,
+    );
+    drop(data);
+
+    create_embed_response_ephemeral(&ctx.http, interaction, embed, ephemeral).await
+}
+
+/// Lists the channels as mentions, an empty list allows any channel.
+pub fn format_channels(channels: &[ChannelId]) -> String {
+    match channels.is_empty() {
+        true => tr(SETTINGS_ANY).to_string(),
+        false => channels
+            .iter()
+            .map(|channel_id| channel_id.mention().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+fn get_channel_option(options: &[CommandDataOption]) -> Result<ChannelId, ParrotError> {
+    options
+        .first()
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(ChannelId)
+        .ok_or(ParrotError::Message(CHANNELS_INVALID_KIND))
+}
@@ -0,0 +1,57 @@
+use serenity::{
+    client::Context, json::Value,
+    model::application::interaction::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{
+    errors::{verify, ParrotError},
+    guild::settings::{GuildSettings, GuildSettingsMap},
+    messaging::message::ParrotMusicMessage,
+    metrics,
+    utils::create_response_music,
+};
+
+pub const MAX_CROSSFADE_SECS: i64 = 12;
+
+pub async fn crossfade(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "crossfade");
+
+    let seconds = interaction
+        .data
+        .options
+        .first()
+        .and_then(|arg| arg.value.as_ref())
+        .and_then(Value::as_i64)
+        .unwrap_or_default();
+
+    verify(
+        (0..=MAX_CROSSFADE_SECS).contains(&seconds),
+        ParrotError::NotInRange("seconds", seconds as isize, 0, MAX_CROSSFADE_SECS as isize),
+    )?;
+
+    let guild_id = interaction.guild_id.unwrap();
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+    guild_settings.set_crossfade(seconds as u64);
+    guild_settings.save()?;
+    drop(data);
+
+    if seconds == 0 {
+        create_response_music(&ctx.http, interaction, ParrotMusicMessage::CrossfadeOff).await
+    } else {
+        let seconds = seconds as u64;
+        create_response_music(
+            &ctx.http,
+            interaction,
+            ParrotMusicMessage::CrossfadeOn { seconds },
+        )
+        .await
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use serenity::{
+    client::Context, json::Value,
+    model::application::interaction::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{
+    errors::ParrotError,
+    guild::settings::{GuildSettings, GuildSettingsMap},
+    handlers::track_end::update_queue_messages,
+    messaging::message::ParrotMusicMessage,
+    metrics,
+    utils::create_response_music,
+};
+
+pub async fn dedupe(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "dedupe");
+
+    let guild_id = interaction.guild_id.unwrap();
+    let manager = songbird::get(ctx).await.unwrap();
+    let call = manager.get(guild_id).unwrap();
+
+    let no_duplicates = interaction
+        .data
+        .options
+        .first()
+        .and_then(|arg| arg.value.as_ref())
+        .and_then(Value::as_bool);
+
+    if let Some(no_duplicates) = no_duplicates {
+        let mut data = ctx.data.write().await;
+        let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+        let guild_settings = settings
+            .entry(guild_id)
+            .or_insert_with(|| GuildSettings::new(guild_id));
+        guild_settings.set_no_duplicates(no_duplicates);
+        guild_settings.save()?;
+    }
+
+    let handler = call.lock().await;
+    let mut removed = 0;
+
+    handler.queue().modify_queue(|queue| {
+        let urls: Vec<Option<String>> = queue
+            .iter()
+            .map(|track| track.metadata().source_url.clone())
+            .collect();
+
+        let duplicates = find_duplicates(&urls);
+        removed = duplicates.len();
+
+        // back to front, so the positions of the ones left to remove don't shift
+        for idx in duplicates.into_iter().rev() {
+            if let Some(track) = queue.remove(idx) {
+                track.stop().ok();
+            }
+        }
+    });
+
+    let queue = handler.queue().current_queue();
+    drop(handler);
+
+    create_response_music(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::Dedupe {
+            removed,
+            no_duplicates,
+        },
+    )
+    .await?;
+
+    update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
+    Ok(())
+}
+
+/// Positions of the tracks whose source was already queued before them. The first of each is
+/// kept, so the track that's playing is never one of them.
+pub fn find_duplicates(urls: &[Option<String>]) -> Vec<usize> {
+    let mut seen = HashSet::new();
+
+    urls.iter()
+        .enumerate()
+        .filter_map(|(idx, url)| match url {
+            Some(url) if !seen.insert(url) => Some(idx),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use std::{cmp::min, ops::Add, time::Duration};
+
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    futures::StreamExt,
+    model::application::interaction::{
+        application_command::ApplicationCommandInteraction, InteractionResponseType,
+    },
+};
+
+use crate::{
+    commands::queue::build_nav_btns,
+    errors::ParrotError,
+    lyrics::{default_providers, find_lyrics, paginate_lyrics, Lyrics, LyricsQuery},
+    messaging::locale::tr,
+    messaging::message::ParrotMusicMessage,
+    messaging::messages::{LYRICS_NOT_FOUND, LYRICS_PROVIDED_BY, QUEUE_PAGE, QUEUE_PAGE_OF},
+    metrics,
+    utils::create_response_music,
+};
+
+/// Embed descriptions are capped at 4096 characters, but shorter pages are easier to follow.
+const LYRICS_PAGE_SIZE: usize = 1500;
+const LYRICS_TIMEOUT: u64 = 3600;
+
+pub async fn lyrics(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "lyrics");
+
+    let guild_id = interaction.guild_id.unwrap();
+    let manager = songbird::get(ctx).await.unwrap();
+    let call = manager.get(guild_id).ok_or(ParrotError::NothingPlaying)?;
+
+    let handler = call.lock().await;
+    let track = handler
+        .queue()
+        .current()
+        .ok_or(ParrotError::NothingPlaying)?;
+    drop(handler);
+
+    let metadata = track.metadata().clone();
+    let query =
+        LyricsQuery::from_metadata(&metadata).ok_or(ParrotError::Message(LYRICS_NOT_FOUND))?;
+
+    // looking through the providers can take a while, so acknowledge the command first
+    create_response_music(&ctx.http, interaction, ParrotMusicMessage::LyricsSearch).await?;
+
+    let lyrics = find_lyrics(&default_providers(), &query)
+        .await
+        .ok_or(ParrotError::Message(LYRICS_NOT_FOUND))?;
+
+    let pages = paginate_lyrics(&lyrics.text, LYRICS_PAGE_SIZE);
+    let title = metadata.title.unwrap_or(query.title);
+    let url = metadata.source_url;
+
+    let mut message = interaction
+        .edit_original_interaction_response(&ctx.http, |message| {
+            message
+                .content(" ")
+                .add_embed(create_lyrics_embed(
+                    &title,
+                    url.as_deref(),
+                    &lyrics,
+                    &pages,
+                    0,
+                ))
+                .components(|components| build_nav_btns(components, 0, pages.len()))
+        })
+        .await?;
+
+    let mut cib = message
+        .await_component_interactions(ctx)
+        .timeout(Duration::from_secs(LYRICS_TIMEOUT))
+        .build();
+
+    let mut page = 0;
+    let num_pages = pages.len();
+
+    while let Some(mci) = cib.next().await {
+        page = match mci.data.custom_id.as_str() {
+            "<<" => 0,
+            "<" => page.saturating_sub(1),
+            ">" => min(page.add(1), num_pages - 1),
+            ">>" => num_pages - 1,
+            _ => continue,
+        };
+
+        mci.create_interaction_response(&ctx, |r| {
+            r.kind(InteractionResponseType::UpdateMessage);
+            r.interaction_response_data(|d| {
+                d.add_embed(create_lyrics_embed(
+                    &title,
+                    url.as_deref(),
+                    &lyrics,
+                    &pages,
+                    page,
+                ));
+                d.components(|components| build_nav_btns(components, page, num_pages))
+            })
+        })
+        .await?;
+    }
+
+    message
+        .edit(&ctx.http, |edit| edit.components(|f| f))
+        .await
+        .ok();
+
+    Ok(())
+}
+
+fn create_lyrics_embed(
+    title: &str,
+    url: Option<&str>,
+    lyrics: &Lyrics,
+    pages: &[String],
+    page: usize,
+) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+
+    embed.title(title);
+    if let Some(url) = url {
+        embed.url(url);
+    }
+
+    embed.description(&pages[page]);
+
+    embed.footer(|f| {
+        f.text(format!(
+            "{} {} • {} {} {} {}",
+            tr(LYRICS_PROVIDED_BY),
+            lyrics.provider,
+            tr(QUEUE_PAGE),
+            page + 1,
+            tr(QUEUE_PAGE_OF),
+            pages.len(),
+        ))
+    });
+
+    embed
+}
@@ -1,12 +1,12 @@
 use serenity::{
-    builder::{CreateComponents, CreateInputText},
+    builder::{CreateComponents, CreateEmbed, CreateInputText},
     client::Context,
     collector::ModalInteractionCollectorBuilder,
     futures::StreamExt,
     model::{
         application::interaction::application_command::ApplicationCommandInteraction,
         prelude::{
-            component::{ActionRowComponent, InputTextStyle},
+            component::{ActionRow, ActionRowComponent, InputTextStyle},
             interaction::InteractionResponseType,
         },
     },
@@ -14,10 +14,14 @@ use serenity::{
 
 use crate::{
     errors::ParrotError,
-    guild::settings::{GuildSettings, GuildSettingsMap},
+    guild::{
+        rules::parse_rules,
+        settings::{GuildSettings, GuildSettingsMap},
+    },
+    messaging::locale::tr,
     messaging::messages::{
-        DOMAIN_FORM_ALLOWED_PLACEHOLDER, DOMAIN_FORM_ALLOWED_TITLE, DOMAIN_FORM_BANNED_PLACEHOLDER,
-        DOMAIN_FORM_BANNED_TITLE, DOMAIN_FORM_TITLE,
+        DOMAIN_FORM_INVALID_RULES, DOMAIN_FORM_RULES_PLACEHOLDER, DOMAIN_FORM_RULES_TITLE,
+        DOMAIN_FORM_TITLE,
     },
     metrics,
 };
@@ -37,51 +41,14 @@ pub async fn allow(
         .entry(guild_id)
         .or_insert_with(|| GuildSettings::new(guild_id));
 
-    // transform the domain sets from the settings into a string
-    let allowed_str = guild_settings
-        .allowed_domains
-        .clone()
-        .into_iter()
-        .collect::<Vec<String>>()
-        .join(";");
-
-    let banned_str = guild_settings
-        .banned_domains
-        .clone()
-        .into_iter()
-        .collect::<Vec<String>>()
-        .join(";");
-
+    let components = create_domain_form(guild_settings);
     drop(data);
 
-    let mut allowed_input = CreateInputText::default();
-    allowed_input
-        .label(DOMAIN_FORM_ALLOWED_TITLE)
-        .custom_id("allowed_domains")
-        .style(InputTextStyle::Paragraph)
-        .placeholder(DOMAIN_FORM_ALLOWED_PLACEHOLDER)
-        .value(allowed_str)
-        .required(false);
-
-    let mut banned_input = CreateInputText::default();
-    banned_input
-        .label(DOMAIN_FORM_BANNED_TITLE)
-        .custom_id("banned_domains")
-        .style(InputTextStyle::Paragraph)
-        .placeholder(DOMAIN_FORM_BANNED_PLACEHOLDER)
-        .value(banned_str)
-        .required(false);
-
-    let mut components = CreateComponents::default();
-    components
-        .create_action_row(|r| r.add_input_text(allowed_input))
-        .create_action_row(|r| r.add_input_text(banned_input));
-
     interaction
         .create_interaction_response(&ctx.http, |r| {
             r.kind(InteractionResponseType::Modal);
             r.interaction_response_data(|d| {
-                d.title(DOMAIN_FORM_TITLE);
+                d.title(tr(DOMAIN_FORM_TITLE));
                 d.custom_id("manage_domains");
                 d.set_components(components)
             })
@@ -98,33 +65,22 @@ pub async fn allow(
             let mut data = ctx.data.write().await;
             let settings = data.get_mut::<GuildSettingsMap>().unwrap();
 
-            let inputs: Vec<_> = int
-                .data
-                .components
-                .iter()
-                .flat_map(|r| r.components.iter())
-                .collect();
-
             let guild_settings = settings.get_mut(&guild_id).unwrap();
-
-            for input in inputs.iter() {
-                if let ActionRowComponent::InputText(it) = input {
-                    if it.custom_id == "allowed_domains" {
-                        guild_settings.set_allowed_domains(&it.value);
-                    }
-
-                    if it.custom_id == "banned_domains" {
-                        guild_settings.set_banned_domains(&it.value);
-                    }
-                }
+            let applied = apply_domain_form(guild_settings, &int.data.components);
+            if applied.is_ok() {
+                guild_settings.save().unwrap();
             }
-
-            guild_settings.update_domains();
-            guild_settings.save().unwrap();
+            drop(data);
 
             // it's now safe to close the modal, so send a response to it
-            int.create_interaction_response(&ctx.http, |r| {
-                r.kind(InteractionResponseType::DeferredUpdateMessage)
+            int.create_interaction_response(&ctx.http, |r| match &applied {
+                Ok(()) => r.kind(InteractionResponseType::DeferredUpdateMessage),
+                Err(invalid) => r
+                    .kind(InteractionResponseType::ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.add_embed(create_invalid_rules_embed(invalid))
+                            .ephemeral(true)
+                    }),
             })
             .await
             .ok();
@@ -134,3 +90,57 @@ pub async fn allow(
 
     Ok(())
 }
+
+/// The input of the form that edits the source rules, filled with the current ones.
+pub fn create_domain_form(guild_settings: &GuildSettings) -> CreateComponents {
+    let rules_str = guild_settings
+        .source_rules
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let mut rules_input = CreateInputText::default();
+    rules_input
+        .label(tr(DOMAIN_FORM_RULES_TITLE))
+        .custom_id("source_rules")
+        .style(InputTextStyle::Paragraph)
+        .placeholder(tr(DOMAIN_FORM_RULES_PLACEHOLDER))
+        .value(rules_str)
+        .required(false);
+
+    let mut components = CreateComponents::default();
+    components.create_action_row(|r| r.add_input_text(rules_input));
+
+    components
+}
+
+/// Updates the source rules with what was submitted through the form. Nothing changes
+/// when any of the rules is invalid, those are returned instead.
+pub fn apply_domain_form(
+    guild_settings: &mut GuildSettings,
+    rows: &[ActionRow],
+) -> Result<(), Vec<String>> {
+    for input in rows.iter().flat_map(|r| r.components.iter()) {
+        if let ActionRowComponent::InputText(it) = input {
+            if it.custom_id == "source_rules" {
+                guild_settings.set_source_rules(parse_rules(&it.value)?);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Points out the rules that couldn't be read.
+pub fn create_invalid_rules_embed(invalid: &[String]) -> CreateEmbed {
+    let rules = invalid
+        .iter()
+        .map(|rule| format!("`{}`", rule))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut embed = CreateEmbed::default();
+    embed.description(format!("{}\n{}", tr(DOMAIN_FORM_INVALID_RULES), rules));
+    embed
+}
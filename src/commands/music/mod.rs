@@ -1,15 +1,23 @@
+pub mod autocomplete;
 pub mod autopause;
+pub mod channels;
 pub mod clear;
+pub mod crossfade;
+pub mod dedupe;
 pub mod leave;
+pub mod lyrics;
 pub mod manage_sources;
+pub mod move_track;
 pub mod now_playing;
 pub mod pause;
 pub mod play;
 pub mod queue;
+pub mod queue_file;
 pub mod remove;
 pub mod repeat;
 pub mod resume;
 pub mod seek;
+pub mod settings;
 pub mod shuffle;
 pub mod skip;
 pub mod stop;
@@ -19,7 +27,8 @@ pub mod volume;
 pub mod voteskip;
 
 pub use self::{
-    autopause::*, clear::*, leave::*, manage_sources::*, now_playing::*, pause::*, play::*,
-    queue::*, remove::*, repeat::*, resume::*, seek::*, shuffle::*, skip::*, stop::*, summon::*,
-    version::*, volume::*, voteskip::*,
+    autocomplete::*, autopause::*, channels::*, clear::*, crossfade::*, dedupe::*, leave::*,
+    lyrics::*, manage_sources::*, move_track::*, now_playing::*, pause::*, play::*, queue::*,
+    queue_file::*, remove::*, repeat::*, resume::*, seek::*, settings::*, shuffle::*, skip::*,
+    stop::*, summon::*, version::*, volume::*, voteskip::*,
 };
@@ -0,0 +1,80 @@
+use serenity::{
+    client::Context, json::Value,
+    model::application::interaction::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{
+    commands::autocomplete::resolve_track,
+    errors::{verify, ParrotError},
+    handlers::track_end::update_queue_messages,
+    messaging::message::ParrotMusicMessage,
+    messaging::messages::FAIL_NO_SONG_ON_INDEX,
+    metrics,
+    utils::create_response_music,
+};
+
+pub async fn move_track(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "move");
+
+    let guild_id = interaction.guild_id.unwrap();
+    let manager = songbird::get(ctx).await.unwrap();
+    let call = manager.get(guild_id).unwrap();
+
+    let args = interaction.data.options.clone();
+    let from = args
+        .iter()
+        .find(|arg| arg.name == "from")
+        .and_then(|arg| arg.value.as_ref())
+        .and_then(Value::as_str)
+        .unwrap();
+    let to = args
+        .iter()
+        .find(|arg| arg.name == "to")
+        .and_then(|arg| arg.value.as_ref())
+        .and_then(Value::as_u64)
+        .unwrap() as usize;
+
+    let handler = call.lock().await;
+    let queue = handler.queue().current_queue();
+    let queue_len = queue.len();
+
+    verify(queue_len > 1, ParrotError::QueueEmpty)?;
+
+    let from = verify(
+        resolve_track(&queue, from).filter(|from| *from > 0 && *from < queue_len),
+        ParrotError::Message(FAIL_NO_SONG_ON_INDEX),
+    )?;
+    verify(
+        to > 0 && to < queue_len,
+        ParrotError::NotInRange("to", to as isize, 1, queue_len as isize - 1),
+    )?;
+
+    handler.queue().modify_queue(|queue| {
+        if let Some(track) = queue.remove(from) {
+            queue.insert(to, track);
+        }
+    });
+
+    // refetch the queue after modification
+    let queue = handler.queue().current_queue();
+    drop(handler);
+
+    let metadata = queue[to].metadata();
+
+    create_response_music(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::Move {
+            title: metadata.title.clone().unwrap_or_default(),
+            url: metadata.source_url.clone().unwrap_or_default(),
+            position: to,
+        },
+    )
+    .await?;
+
+    update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
+    Ok(())
+}
@@ -1,14 +1,30 @@
+use std::time::Duration;
+
 use serenity::{
+    builder::CreateComponents,
     client::Context,
-    model::application::interaction::application_command::ApplicationCommandInteraction,
+    futures::StreamExt,
+    model::application::interaction::{
+        application_command::ApplicationCommandInteraction, InteractionResponseType,
+    },
 };
 
 use crate::{
+    commands::seek::track_chapters,
     errors::ParrotError,
+    messaging::locale::tr,
+    messaging::messages::SEEK_CHAPTER_PLACEHOLDER,
     metrics,
-    utils::{create_embed_response, create_now_playing_embed},
+    sources::metadata_cache::Chapter,
+    utils::{create_embed_response, create_now_playing_embed, get_human_readable_timestamp},
 };
 
+const CHAPTER_MENU_ID: &str = "chapter";
+const CHAPTER_MENU_TIMEOUT: u64 = 600;
+
+/// Discord caps select menus to 25 options.
+const CHAPTER_MENU_SIZE: usize = 25;
+
 pub async fn now_playing(
     ctx: &Context,
     interaction: &mut ApplicationCommandInteraction,
@@ -24,7 +40,101 @@ pub async fn now_playing(
         .queue()
         .current()
         .ok_or(ParrotError::NothingPlaying)?;
+    drop(handler);
 
     let embed = create_now_playing_embed(&track).await;
-    create_embed_response(&ctx.http, interaction, embed).await
+
+    let chapters = track_chapters(&track);
+    if chapters.is_empty() {
+        return create_embed_response(&ctx.http, interaction, embed).await;
+    }
+
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message
+                        .add_embed(embed)
+                        .components(|components| build_chapter_menu(components, &chapters))
+                })
+        })
+        .await?;
+
+    let mut message = interaction.get_interaction_response(&ctx.http).await?;
+
+    let mut cib = message
+        .await_component_interactions(ctx)
+        .timeout(Duration::from_secs(CHAPTER_MENU_TIMEOUT))
+        .build();
+
+    while let Some(mci) = cib.next().await {
+        if mci.data.custom_id != CHAPTER_MENU_ID {
+            continue;
+        }
+
+        let chapter = mci
+            .data
+            .values
+            .first()
+            .and_then(|value| value.parse::<usize>().ok())
+            .and_then(|idx| chapters.get(idx));
+
+        // only seek if the track the menu was built for is still playing
+        let handler = call.lock().await;
+        let current = handler.queue().current();
+        drop(handler);
+
+        let Some(current) = current.filter(|current| current.uuid() == track.uuid()) else {
+            break;
+        };
+
+        if let Some(chapter) = chapter {
+            current.seek_time(chapter.start()).ok();
+        }
+
+        let embed = create_now_playing_embed(&current).await;
+
+        mci.create_interaction_response(&ctx, |r| {
+            r.kind(InteractionResponseType::UpdateMessage);
+            r.interaction_response_data(|d| {
+                d.add_embed(embed);
+                d.components(|components| build_chapter_menu(components, &chapters))
+            })
+        })
+        .await?;
+    }
+
+    message
+        .edit(&ctx.http, |edit| edit.components(|f| f))
+        .await
+        .ok();
+
+    Ok(())
+}
+
+fn build_chapter_menu<'a>(
+    components: &'a mut CreateComponents,
+    chapters: &[Chapter],
+) -> &'a mut CreateComponents {
+    components.create_action_row(|action_row| {
+        action_row.create_select_menu(|menu| {
+            menu.custom_id(CHAPTER_MENU_ID)
+                .placeholder(tr(SEEK_CHAPTER_PLACEHOLDER))
+                .options(|options| {
+                    for (idx, chapter) in chapters.iter().take(CHAPTER_MENU_SIZE).enumerate() {
+                        let start = get_human_readable_timestamp(Some(chapter.start()));
+                        let label = match chapter.title.trim() {
+                            "" => format!("Chapter {}", idx + 1),
+                            title => title.chars().take(100).collect(),
+                        };
+
+                        options.create_option(|option| {
+                            option.label(label).value(idx).description(start)
+                        });
+                    }
+                    options
+                })
+        })
+    })
 }
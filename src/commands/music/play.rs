@@ -1,33 +1,59 @@
 use std::{cmp::Ordering, error::Error as StdError, sync::Arc, time::Duration};
 
+use lazy_static::lazy_static;
 use serenity::{
-    builder::CreateEmbed, client::Context,
-    model::application::interaction::application_command::ApplicationCommandInteraction,
-    prelude::Mutex,
+    builder::CreateEmbed,
+    client::Context,
+    model::{
+        application::interaction::application_command::ApplicationCommandInteraction, id::UserId,
+    },
+    prelude::{Mutex, TypeMapKey},
+};
+use songbird::{
+    input::{Input, Metadata, Restartable},
+    tracks::TrackHandle,
+    Call,
 };
-use songbird::{input::Restartable, tracks::TrackHandle, Call};
 use url::Url;
 
 use crate::{
     commands::music::{skip::force_skip_top_track, summon::summon},
     errors::{verify, ParrotError},
-    guild::settings::{GuildSettings, GuildSettingsMap},
+    guild::{
+        filter::{ContentFilter, FilterViolation},
+        settings::{GuildSettings, GuildSettingsMap},
+        theme::{current_theme, render_template},
+    },
     handlers::track_end::update_queue_messages,
+    messaging::locale::tr,
     messaging::message::ParrotMusicMessage,
     messaging::messages::{
-        PLAY_QUEUE, PLAY_TOP, SPOTIFY_AUTH_FAILED, TRACK_DURATION, TRACK_TIME_TO_PLAY,
+        PLAY_PLAYLIST_ALL_SKIPPED, PLAY_QUEUE, PLAY_TOP, SPOTIFY_AUTH_FAILED, TRACK_DUPLICATE,
+        TRACK_DURATION, TRACK_FILTERED_AGE_RESTRICTED, TRACK_FILTERED_BLOCKED_WORD,
+        TRACK_FILTERED_CATEGORY, TRACK_TIME_TO_PLAY,
     },
     metrics,
     sources::{
+        apple_music::APPLE_MUSIC,
+        audio_cache::prefetch_queue,
+        deezer::DEEZER,
+        metadata_cache::METADATA_CACHE,
+        provider::Provider,
         spotify::{Spotify, SPOTIFY},
-        youtube::{YouTube, YouTubeRestartable},
+        tidal::TIDAL,
+        youtube::YouTubeRestartable,
     },
     utils::{
-        compare_domains, create_now_playing_embed, create_response_music, edit_embed_response,
-        edit_response_music, get_human_readable_timestamp,
+        apply_theme, create_now_playing_embed, create_response_music, edit_embed_response,
+        edit_response_music, get_human_readable_timestamp, truncate, EMBED_FIELD_MAX_LEN,
     },
 };
 
+lazy_static! {
+    /// Where keyword searches end up, for the source rules to decide on.
+    static ref YOUTUBE_SEARCH_URL: Url = Url::parse("https://www.youtube.com/results").unwrap();
+}
+
 #[derive(Clone, Copy)]
 pub enum Mode {
     End,
@@ -46,6 +72,41 @@ pub enum QueryType {
     PlaylistLink(String),
 }
 
+/// The user who queued a track, kept in the track's own type map.
+pub struct RequestedBy;
+
+impl TypeMapKey for RequestedBy {
+    type Value = UserId;
+}
+
+/// Who is queueing tracks, and what the guild keeps out of its queue.
+#[derive(Clone)]
+pub struct EnqueueOptions {
+    pub requester: UserId,
+    pub no_duplicates: bool,
+    pub content_filter: ContentFilter,
+}
+
+impl EnqueueOptions {
+    pub async fn new(ctx: &Context, interaction: &ApplicationCommandInteraction) -> Self {
+        let guild_id = interaction.guild_id.unwrap();
+
+        let data = ctx.data.read().await;
+        let settings = data.get::<GuildSettingsMap>().unwrap();
+        let guild_settings = settings.get(&guild_id);
+
+        EnqueueOptions {
+            requester: interaction.user.id,
+            no_duplicates: guild_settings
+                .map(|guild_settings| guild_settings.no_duplicates)
+                .unwrap_or_default(),
+            content_filter: guild_settings
+                .map(|guild_settings| guild_settings.content_filter.clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
 #[tracing::instrument(skip(ctx, interaction), err)]
 pub async fn play(
     ctx: &Context,
@@ -85,6 +146,8 @@ pub async fn play(
     summon(ctx, interaction, false).await?;
     let call = manager.get(guild_id).unwrap();
 
+    let options = EnqueueOptions::new(ctx, interaction).await;
+
     tracing::info!(url = %url, "adding url to queue");
 
     // determine whether this is a link or a query string
@@ -92,12 +155,19 @@ pub async fn play(
         Ok(url_data) => match url_data.host_str() {
             Some("open.spotify.com") => {
                 let spotify = SPOTIFY.lock().await;
-                let spotify = verify(spotify.as_ref(), ParrotError::Other(SPOTIFY_AUTH_FAILED))?;
+                let spotify = verify(spotify.as_ref(), ParrotError::Message(SPOTIFY_AUTH_FAILED))?;
 
                 spotify.request_token().await?;
 
                 Some(Spotify::extract(spotify, url).await?)
             }
+            Some("music.apple.com") => Some(APPLE_MUSIC.extract(url).await?),
+            Some("deezer.com" | "www.deezer.com" | "deezer.page.link" | "link.deezer.com") => {
+                Some(DEEZER.extract(url).await?)
+            }
+            Some("tidal.com" | "www.tidal.com" | "listen.tidal.com") => {
+                Some(TIDAL.extract(url).await?)
+            }
             Some(other) => {
                 let mut data = ctx.data.write().await;
                 let settings = data.get_mut::<GuildSettingsMap>().unwrap();
@@ -105,17 +175,7 @@ pub async fn play(
                     .entry(guild_id)
                     .or_insert_with(|| GuildSettings::new(guild_id));
 
-                let is_allowed = guild_settings
-                    .allowed_domains
-                    .iter()
-                    .any(|d| compare_domains(d, other));
-
-                let is_banned = guild_settings
-                    .banned_domains
-                    .iter()
-                    .any(|d| compare_domains(d, other));
-
-                if is_banned || (guild_settings.banned_domains.is_empty() && !is_allowed) {
+                if !guild_settings.is_source_allowed(&url_data) {
                     return create_response_music(
                         &ctx.http,
                         interaction,
@@ -126,7 +186,7 @@ pub async fn play(
                     .await;
                 }
 
-                YouTube::extract(url)
+                Provider::extract(url)
             }
             None => None,
         },
@@ -137,10 +197,8 @@ pub async fn play(
                 .entry(guild_id)
                 .or_insert_with(|| GuildSettings::new(guild_id));
 
-            if guild_settings.banned_domains.contains("youtube.com")
-                || (guild_settings.banned_domains.is_empty()
-                    && !guild_settings.allowed_domains.contains("youtube.com"))
-            {
+            // keywords are searched on youtube, so they follow its rules
+            if !guild_settings.is_source_allowed(&YOUTUBE_SEARCH_URL) {
                 return create_response_music(
                     &ctx.http,
                     interaction,
@@ -168,75 +226,102 @@ pub async fn play(
     let queue_was_empty = handler.queue().is_empty();
     drop(handler);
 
+    // tracks of a list that are already queued, filtered out or not found are left out
+    let mut skipped = 0;
+
     match mode {
         Mode::End => match query_type.clone() {
             QueryType::Keywords(_) | QueryType::VideoLink(_) => {
-                let queue = enqueue_track(&call, &query_type).await?;
+                let queue = enqueue_track(&call, &query_type, &options).await?;
                 update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
             }
             QueryType::PlaylistLink(url) => {
-                let urls = YouTubeRestartable::ytdl_playlist(&url, mode)
-                    .await
-                    .ok_or(ParrotError::Other("failed to fetch playlist"))?;
+                let mut playlist = YouTubeRestartable::ytdl_playlist(&url, mode).await?;
 
-                for url in urls.iter() {
-                    let queue =
-                        match enqueue_track(&call, &QueryType::VideoLink(url.to_string())).await {
-                            Ok(queue) => queue,
-                            Err(err) => {
-                                tracing::error!(err = ?err, url = %url, "Failed to enqueue track");
-                                continue;
-                            }
-                        };
+                while let Some(url) = playlist.next().await? {
+                    let queue = match enqueue_track(
+                        &call,
+                        &QueryType::VideoLink(url.to_string()),
+                        &options,
+                    )
+                    .await
+                    {
+                        Ok(queue) => queue,
+                        Err(err) => {
+                            tracing::error!(err = ?err, url = %url, "Failed to enqueue track");
+                            skipped += 1;
+                            continue;
+                        }
+                    };
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
             QueryType::KeywordList(keywords_list) => {
                 for keywords in keywords_list.iter() {
-                    let queue =
-                        enqueue_track(&call, &QueryType::Keywords(keywords.to_string())).await?;
+                    let query_type = QueryType::Keywords(keywords.to_string());
+                    let queue = match enqueue_track(&call, &query_type, &options).await {
+                        Ok(queue) => queue,
+                        Err(err) => {
+                            tracing::error!(err = ?err, keywords = %keywords, "Failed to enqueue track");
+                            skipped += 1;
+                            continue;
+                        }
+                    };
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
         },
         Mode::Next => match query_type.clone() {
             QueryType::Keywords(_) | QueryType::VideoLink(_) => {
-                let queue = insert_track(&call, &query_type, 1).await?;
+                let queue = insert_track(&call, &query_type, 1, &options).await?;
                 update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
             }
             QueryType::PlaylistLink(url) => {
-                let urls = YouTubeRestartable::ytdl_playlist(&url, mode)
-                    .await
-                    .ok_or(ParrotError::Other("failed to fetch playlist"))?;
+                let mut playlist = YouTubeRestartable::ytdl_playlist(&url, mode).await?;
+
+                let mut insert_idx = 1;
 
-                for (idx, url) in urls.into_iter().enumerate() {
+                while let Some(url) = playlist.next().await? {
                     let queue = match insert_track(
                         &call,
                         &QueryType::VideoLink(url.clone()),
-                        idx + 1,
+                        insert_idx,
+                        &options,
                     )
                     .await
                     {
                         Ok(queue) => queue,
                         Err(err) => {
                             tracing::error!(err = ?err, url = %url, "Failed to insert track");
+                            skipped += 1;
                             continue;
                         }
                     };
+                    insert_idx += 1;
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
             QueryType::KeywordList(keywords_list) => {
-                for (idx, keywords) in keywords_list.into_iter().enumerate() {
-                    let queue =
-                        insert_track(&call, &QueryType::Keywords(keywords), idx + 1).await?;
+                let mut insert_idx = 1;
+
+                for keywords in keywords_list.into_iter() {
+                    let query_type = QueryType::Keywords(keywords.clone());
+                    let queue = match insert_track(&call, &query_type, insert_idx, &options).await {
+                        Ok(queue) => queue,
+                        Err(err) => {
+                            tracing::error!(err = ?err, keywords = %keywords, "Failed to insert track");
+                            skipped += 1;
+                            continue;
+                        }
+                    };
+                    insert_idx += 1;
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
         },
         Mode::Jump => match query_type.clone() {
             QueryType::Keywords(_) | QueryType::VideoLink(_) => {
-                let mut queue = enqueue_track(&call, &query_type).await?;
+                let mut queue = enqueue_track(&call, &query_type, &options).await?;
 
                 if !queue_was_empty {
                     rotate_tracks(&call, 1).await.ok();
@@ -246,45 +331,61 @@ pub async fn play(
                 update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
             }
             QueryType::PlaylistLink(url) => {
-                let urls = YouTubeRestartable::ytdl_playlist(&url, mode)
-                    .await
-                    .ok_or(ParrotError::Other("failed to fetch playlist"))?;
+                let mut playlist = YouTubeRestartable::ytdl_playlist(&url, mode).await?;
 
                 let mut insert_idx = 1;
+                let mut inserted = 0;
 
-                for (i, url) in urls.into_iter().enumerate() {
-                    let mut queue =
-                        match insert_track(&call, &QueryType::VideoLink(url.clone()), insert_idx)
-                            .await
-                        {
-                            Ok(queue) => queue,
-                            Err(err) => {
-                                tracing::error!(err = ?err, url = %url, "Failed to insert track");
-                                continue;
-                            }
-                        };
+                while let Some(url) = playlist.next().await? {
+                    let mut queue = match insert_track(
+                        &call,
+                        &QueryType::VideoLink(url.clone()),
+                        insert_idx,
+                        &options,
+                    )
+                    .await
+                    {
+                        Ok(queue) => queue,
+                        Err(err) => {
+                            tracing::error!(err = ?err, url = %url, "Failed to insert track");
+                            skipped += 1;
+                            continue;
+                        }
+                    };
 
-                    if i == 0 && !queue_was_empty {
+                    if inserted == 0 && !queue_was_empty {
                         queue = force_skip_top_track(&call.lock().await).await?;
                     } else {
                         insert_idx += 1;
                     }
+                    inserted += 1;
 
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
             QueryType::KeywordList(keywords_list) => {
                 let mut insert_idx = 1;
+                let mut inserted = 0;
 
-                for (i, keywords) in keywords_list.into_iter().enumerate() {
-                    let mut queue =
-                        insert_track(&call, &QueryType::Keywords(keywords), insert_idx).await?;
+                for keywords in keywords_list.into_iter() {
+                    let query_type = QueryType::Keywords(keywords.clone());
+                    let mut queue = match insert_track(&call, &query_type, insert_idx, &options)
+                        .await
+                    {
+                        Ok(queue) => queue,
+                        Err(err) => {
+                            tracing::error!(err = ?err, keywords = %keywords, "Failed to insert track");
+                            skipped += 1;
+                            continue;
+                        }
+                    };
 
-                    if i == 0 && !queue_was_empty {
+                    if inserted == 0 && !queue_was_empty {
                         queue = force_skip_top_track(&call.lock().await).await?;
                     } else {
                         insert_idx += 1;
                     }
+                    inserted += 1;
 
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
@@ -292,25 +393,34 @@ pub async fn play(
         },
         Mode::All | Mode::Reverse | Mode::Shuffle => match query_type.clone() {
             QueryType::VideoLink(url) | QueryType::PlaylistLink(url) => {
-                let urls = YouTubeRestartable::ytdl_playlist(&url, mode)
-                    .await
-                    .ok_or(ParrotError::Other("failed to fetch playlist"))?;
+                let mut playlist = YouTubeRestartable::ytdl_playlist(&url, mode).await?;
 
-                for url in urls.into_iter() {
-                    let queue = match enqueue_track(&call, &QueryType::VideoLink(url.clone())).await
-                    {
-                        Ok(queue) => queue,
-                        Err(err) => {
-                            tracing::error!(err = ?err, url = %url, "Failed to enqueue track");
-                            continue;
-                        }
-                    };
+                while let Some(url) = playlist.next().await? {
+                    let queue =
+                        match enqueue_track(&call, &QueryType::VideoLink(url.clone()), &options)
+                            .await
+                        {
+                            Ok(queue) => queue,
+                            Err(err) => {
+                                tracing::error!(err = ?err, url = %url, "Failed to enqueue track");
+                                skipped += 1;
+                                continue;
+                            }
+                        };
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
             QueryType::KeywordList(keywords_list) => {
                 for keywords in keywords_list.into_iter() {
-                    let queue = enqueue_track(&call, &QueryType::Keywords(keywords)).await?;
+                    let query_type = QueryType::Keywords(keywords.clone());
+                    let queue = match enqueue_track(&call, &query_type, &options).await {
+                        Ok(queue) => queue,
+                        Err(err) => {
+                            tracing::error!(err = ?err, keywords = %keywords, "Failed to enqueue track");
+                            skipped += 1;
+                            continue;
+                        }
+                    };
                     update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
                 }
             }
@@ -337,6 +447,8 @@ pub async fn play(
         .for_each(|t| t.set_volume(guild_settings.default_volume).unwrap());
     drop(handler);
 
+    prefetch_queue(&queue);
+
     match queue.len().cmp(&1) {
         Ordering::Greater => {
             let estimated_time = calculate_time_until_play(&queue, mode).await.unwrap();
@@ -344,28 +456,46 @@ pub async fn play(
             match (query_type, mode) {
                 (QueryType::VideoLink(_) | QueryType::Keywords(_), Mode::Next) => {
                     let track = queue.get(1).unwrap();
-                    let embed = create_queued_embed(PLAY_TOP, track, estimated_time).await;
+                    let embed = create_queued_embed(tr(PLAY_TOP), track, estimated_time).await;
 
                     edit_embed_response(&ctx.http, interaction, embed).await?;
                 }
                 (QueryType::VideoLink(_) | QueryType::Keywords(_), Mode::End) => {
                     let track = queue.last().unwrap();
-                    let embed = create_queued_embed(PLAY_QUEUE, track, estimated_time).await;
+                    let embed = create_queued_embed(tr(PLAY_QUEUE), track, estimated_time).await;
 
                     edit_embed_response(&ctx.http, interaction, embed).await?;
                 }
                 (QueryType::PlaylistLink(_) | QueryType::KeywordList(_), _) => {
-                    edit_response_music(&ctx.http, interaction, ParrotMusicMessage::PlaylistQueued)
-                        .await?;
+                    edit_response_music(
+                        &ctx.http,
+                        interaction,
+                        ParrotMusicMessage::PlaylistQueued { skipped },
+                    )
+                    .await?;
                 }
                 (_, _) => {}
             }
         }
-        Ordering::Equal => {
-            let track = queue.first().unwrap();
-            let embed = create_now_playing_embed(track).await;
+        Ordering::Equal => match query_type {
+            // the only track that made it in is playing, so say why the rest didn't
+            QueryType::PlaylistLink(_) | QueryType::KeywordList(_) if skipped > 0 => {
+                edit_response_music(
+                    &ctx.http,
+                    interaction,
+                    ParrotMusicMessage::PlaylistQueued { skipped },
+                )
+                .await?;
+            }
+            _ => {
+                let track = queue.first().unwrap();
+                let embed = create_now_playing_embed(track).await;
 
-            edit_embed_response(&ctx.http, interaction, embed).await?;
+                edit_embed_response(&ctx.http, interaction, embed).await?;
+            }
+        },
+        Ordering::Less if skipped > 0 => {
+            return Err(ParrotError::Message(PLAY_PLAYLIST_ALL_SKIPPED));
         }
         // TODO: if the link is not valid (eg has `\` at the end), queue will be 0, figure out how to handle that
         _ => unreachable!(),
@@ -413,30 +543,51 @@ async fn create_queued_embed(
     track: &TrackHandle,
     estimated_time: Duration,
 ) -> CreateEmbed {
+    let theme = current_theme();
     let mut embed = CreateEmbed::default();
     let metadata = track.metadata().clone();
 
-    embed.thumbnail(&metadata.thumbnail.unwrap());
+    if theme.thumbnails {
+        embed.thumbnail(&metadata.thumbnail.unwrap());
+    }
 
-    embed.field(
-        title,
-        &format!(
-            "[**{}**]({})",
-            metadata.title.unwrap(),
-            metadata.source_url.unwrap()
+    let track_title = metadata.title.unwrap();
+    let source_url = metadata.source_url.unwrap();
+    let duration = get_human_readable_timestamp(metadata.duration);
+    let time_to_play = get_human_readable_timestamp(Some(estimated_time));
+
+    // templates are bounded, but repeating placeholders can still make them grow past that
+    let details = match &theme.track_queued_template {
+        Some(template) => truncate(
+            &render_template(
+                template,
+                &[
+                    ("title", &track_title),
+                    ("url", &source_url),
+                    ("duration", &duration),
+                    ("eta", &time_to_play),
+                ],
+            ),
+            EMBED_FIELD_MAX_LEN,
         ),
-        false,
-    );
-
-    let footer_text = format!(
-        "{}{}\n{}{}",
-        TRACK_DURATION,
-        get_human_readable_timestamp(metadata.duration),
-        TRACK_TIME_TO_PLAY,
-        get_human_readable_timestamp(Some(estimated_time))
-    );
-
-    embed.footer(|footer| footer.text(footer_text));
+        None => format!("[**{}**]({})", track_title, source_url),
+    };
+
+    embed.field(title, &details, false);
+
+    if !theme.compact {
+        let footer_text = format!(
+            "{}{}\n{}{}",
+            tr(TRACK_DURATION),
+            duration,
+            tr(TRACK_TIME_TO_PLAY),
+            time_to_play
+        );
+
+        embed.footer(|footer| footer.text(footer_text));
+    }
+
+    apply_theme(&mut embed, &theme);
     embed
 }
 
@@ -454,30 +605,72 @@ async fn get_track_source(query_type: QueryType) -> Result<Restartable, ParrotEr
     }
 }
 
-async fn enqueue_track(
+pub async fn enqueue_track(
     call: &Arc<Mutex<Call>>,
     query_type: &QueryType,
+    options: &EnqueueOptions,
 ) -> Result<Vec<TrackHandle>, ParrotError> {
     // safeguard against ytdl dying on a private/deleted video and killing the playlist
     let source = get_track_source(query_type.clone()).await?;
 
+    let source: Input = source.into();
+
+    // yt-dlp was asked about the track when it was created, so this hits the cache
+    let mut details = source
+        .metadata
+        .source_url
+        .as_deref()
+        .and_then(|url| METADATA_CACHE.get(url))
+        .unwrap_or_default();
+    details.title = details.title.or_else(|| source.metadata.title.clone());
+
+    if let Err(violation) = options.content_filter.check(&details) {
+        let message = match violation {
+            FilterViolation::AgeRestricted => TRACK_FILTERED_AGE_RESTRICTED,
+            FilterViolation::BlockedWord => TRACK_FILTERED_BLOCKED_WORD,
+            FilterViolation::Category => TRACK_FILTERED_CATEGORY,
+        };
+        return Err(ParrotError::Message(message));
+    }
+
     let mut handler = call.lock().await;
-    handler.enqueue_source(source.into());
+    if options.no_duplicates && is_queued(&handler.queue().current_queue(), &source.metadata) {
+        return Err(ParrotError::Message(TRACK_DUPLICATE));
+    }
+
+    let track = handler.enqueue_source(source);
+    track
+        .typemap()
+        .write()
+        .await
+        .insert::<RequestedBy>(options.requester);
 
     Ok(handler.queue().current_queue())
 }
 
+/// Whether a track with the same source is already in the queue.
+fn is_queued(queue: &[TrackHandle], metadata: &Metadata) -> bool {
+    let Some(source_url) = &metadata.source_url else {
+        return false;
+    };
+
+    queue
+        .iter()
+        .any(|track| track.metadata().source_url.as_ref() == Some(source_url))
+}
+
 async fn insert_track(
     call: &Arc<Mutex<Call>>,
     query_type: &QueryType,
     idx: usize,
+    options: &EnqueueOptions,
 ) -> Result<Vec<TrackHandle>, ParrotError> {
     let handler = call.lock().await;
     let queue_size = handler.queue().len();
     drop(handler);
 
     if queue_size <= 1 {
-        let queue = enqueue_track(call, query_type).await?;
+        let queue = enqueue_track(call, query_type, options).await?;
         return Ok(queue);
     }
 
@@ -486,7 +679,7 @@ async fn insert_track(
         ParrotError::NotInRange("index", idx as isize, 1, queue_size as isize),
     )?;
 
-    enqueue_track(call, query_type).await?;
+    enqueue_track(call, query_type, options).await?;
 
     let handler = call.lock().await;
     handler.queue().modify_queue(|queue| {
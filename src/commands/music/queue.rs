@@ -25,15 +25,17 @@ use serenity::{
 use songbird::{tracks::TrackHandle, Event, TrackEvent};
 
 use crate::{
+    commands::queue_file::{export_queue, import_queue},
     errors::ParrotError,
-    guild::cache::GuildCacheMap,
+    guild::{cache::GuildCacheMap, theme::current_theme},
     handlers::track_end::ModifyQueueHandler,
+    messaging::locale::tr,
     messaging::messages::{
         QUEUE_EXPIRED, QUEUE_NOTHING_IS_PLAYING, QUEUE_NOW_PLAYING, QUEUE_NO_SONGS, QUEUE_PAGE,
         QUEUE_PAGE_OF, QUEUE_UP_NEXT,
     },
     metrics,
-    utils::get_human_readable_timestamp,
+    utils::{apply_theme, get_human_readable_timestamp},
 };
 
 const EMBED_PAGE_SIZE: usize = 6;
@@ -45,6 +47,17 @@ pub async fn queue(
 ) -> Result<(), ParrotError> {
     let _timer = metrics::record_command(ctx, "queue");
 
+    let args = interaction.data.options.clone();
+    match args.first() {
+        Some(subcommand) if subcommand.name == "export" => {
+            return export_queue(ctx, interaction, &subcommand.options).await
+        }
+        Some(subcommand) if subcommand.name == "import" => {
+            return import_queue(ctx, interaction, &subcommand.options).await
+        }
+        _ => {}
+    }
+
     let guild_id = interaction.guild_id.unwrap();
     let manager = songbird::get(ctx).await.unwrap();
     let call = manager.get(guild_id).unwrap();
@@ -128,7 +141,7 @@ pub async fn queue(
     message
         .edit(&ctx.http, |edit| {
             let mut embed = CreateEmbed::default();
-            embed.description(QUEUE_EXPIRED);
+            embed.description(tr(QUEUE_EXPIRED));
             edit.set_embed(embed);
             edit.components(|f| f)
         })
@@ -143,11 +156,14 @@ pub async fn queue(
 }
 
 pub fn create_queue_embed(tracks: &[TrackHandle], page: usize) -> CreateEmbed {
+    let theme = current_theme();
     let mut embed: CreateEmbed = CreateEmbed::default();
 
     let description = if !tracks.is_empty() {
         let metadata = tracks[0].metadata();
-        embed.thumbnail(tracks[0].metadata().thumbnail.as_ref().unwrap());
+        if theme.thumbnails {
+            embed.thumbnail(tracks[0].metadata().thumbnail.as_ref().unwrap());
+        }
 
         format!(
             "[{}]({}) • `{}`",
@@ -156,22 +172,25 @@ pub fn create_queue_embed(tracks: &[TrackHandle], page: usize) -> CreateEmbed {
             get_human_readable_timestamp(metadata.duration)
         )
     } else {
-        String::from(QUEUE_NOTHING_IS_PLAYING)
+        String::from(tr(QUEUE_NOTHING_IS_PLAYING))
     };
 
-    embed.field(QUEUE_NOW_PLAYING, &description, false);
-    embed.field(QUEUE_UP_NEXT, &build_queue_page(tracks, page), false);
-
-    embed.footer(|f| {
-        f.text(format!(
-            "{} {} {} {}",
-            QUEUE_PAGE,
-            page + 1,
-            QUEUE_PAGE_OF,
-            calculate_num_pages(tracks),
-        ))
-    });
+    embed.field(tr(QUEUE_NOW_PLAYING), &description, false);
+    embed.field(tr(QUEUE_UP_NEXT), &build_queue_page(tracks, page), false);
+
+    if !theme.compact {
+        embed.footer(|f| {
+            f.text(format!(
+                "{} {} {} {}",
+                tr(QUEUE_PAGE),
+                page + 1,
+                tr(QUEUE_PAGE_OF),
+                calculate_num_pages(tracks),
+            ))
+        });
+    }
 
+    apply_theme(&mut embed, &theme);
     embed
 }
 
@@ -209,7 +228,7 @@ fn build_queue_page(tracks: &[TrackHandle], page: usize) -> String {
         .collect();
 
     if queue.is_empty() {
-        return String::from(QUEUE_NO_SONGS);
+        return String::from(tr(QUEUE_NO_SONGS));
     }
 
     let mut description = String::new();
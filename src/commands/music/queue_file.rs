@@ -0,0 +1,311 @@
+use std::{borrow::Cow, fmt::Write, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    http::AttachmentType,
+    model::{
+        application::interaction::{
+            application_command::{ApplicationCommandInteraction, CommandDataOption},
+            InteractionResponseType,
+        },
+        id::{AttachmentId, UserId},
+    },
+};
+use songbird::tracks::TrackHandle;
+use url::Url;
+
+use crate::{
+    commands::{
+        play::{enqueue_track, EnqueueOptions, QueryType, RequestedBy},
+        summon::summon,
+    },
+    errors::{verify, ParrotError},
+    guild::settings::{GuildSettings, GuildSettingsMap},
+    handlers::track_end::update_queue_messages,
+    messaging::locale::tr,
+    messaging::message::ParrotMusicMessage,
+    messaging::messages::{QUEUE_EXPORTED, QUEUE_IMPORT_INVALID, QUEUE_IMPORT_TOO_LARGE},
+    sources::audio_cache::prefetch_queue,
+    utils::{create_response_music, edit_response_music},
+};
+
+/// Queue files are small, anything bigger than this isn't one.
+const MAX_IMPORT_SIZE: u64 = 1024 * 1024;
+
+/// Every imported track is looked up with yt-dlp, one after the other, so a single import
+/// can't queue more than this.
+const MAX_IMPORT_TRACKS: usize = 500;
+
+const REQUESTED_BY_ATTRIBUTE: &str = "requested-by";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueFormat {
+    Json,
+    M3u,
+}
+
+impl QueueFormat {
+    pub fn from_filename(filename: &str) -> Option<QueueFormat> {
+        let (_, extension) = filename.rsplit_once('.')?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "m3u" | "m3u8" => Some(Self::M3u),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::M3u => "m3u",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct QueueEntry {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Length in seconds, missing for livestreams.
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub requested_by: Option<UserId>,
+}
+
+impl QueueEntry {
+    async fn from_track(track: &TrackHandle) -> Option<QueueEntry> {
+        let metadata = track.metadata();
+        let requested_by = track.typemap().read().await.get::<RequestedBy>().copied();
+
+        Some(QueueEntry {
+            url: metadata.source_url.clone()?,
+            title: metadata.title.clone(),
+            duration: metadata.duration.as_ref().map(Duration::as_secs),
+            requested_by,
+        })
+    }
+}
+
+pub fn write_queue(entries: &[QueueEntry], format: QueueFormat) -> Result<String, ParrotError> {
+    match format {
+        QueueFormat::Json => Ok(serde_json::to_string_pretty(entries)?),
+        QueueFormat::M3u => {
+            let mut playlist = String::from("#EXTM3U\n");
+
+            for entry in entries {
+                let duration = entry.duration.map(|d| d as i64).unwrap_or(-1);
+                let title = entry.title.as_deref().unwrap_or(&entry.url);
+
+                let _ = write!(playlist, "#EXTINF:{}", duration);
+                if let Some(user_id) = entry.requested_by {
+                    let _ = write!(playlist, " {}=\"{}\"", REQUESTED_BY_ATTRIBUTE, user_id);
+                }
+                let _ = writeln!(playlist, ",{}\n{}", title.replace('\n', " "), entry.url);
+            }
+
+            Ok(playlist)
+        }
+    }
+}
+
+pub fn read_queue(contents: &str, format: QueueFormat) -> Result<Vec<QueueEntry>, ParrotError> {
+    match format {
+        QueueFormat::Json => {
+            serde_json::from_str(contents).map_err(|_| ParrotError::Message(QUEUE_IMPORT_INVALID))
+        }
+        QueueFormat::M3u => {
+            let mut entries = vec![];
+            let mut info = QueueEntry::default();
+
+            for line in contents.lines().map(str::trim) {
+                if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+                    info = parse_extinf(extinf);
+                } else if !line.is_empty() && !line.starts_with('#') {
+                    entries.push(QueueEntry {
+                        url: line.to_string(),
+                        ..std::mem::take(&mut info)
+                    });
+                }
+            }
+
+            Ok(entries)
+        }
+    }
+}
+
+/// Parses `<duration> [attributes],<title>`, the part of an `#EXTINF` line after the colon.
+fn parse_extinf(extinf: &str) -> QueueEntry {
+    let (info, title) = extinf.split_once(',').unwrap_or((extinf, ""));
+    let mut info = info.split_whitespace();
+
+    let duration = info
+        .next()
+        .and_then(|duration| duration.parse::<i64>().ok())
+        .and_then(|duration| u64::try_from(duration).ok());
+
+    let requested_by = info
+        .filter_map(|attribute| attribute.split_once('='))
+        .find(|(key, _)| *key == REQUESTED_BY_ATTRIBUTE)
+        .and_then(|(_, value)| value.trim_matches('"').parse::<u64>().ok())
+        .map(UserId);
+
+    QueueEntry {
+        url: String::new(),
+        title: Some(title.trim().to_string()).filter(|title| !title.is_empty()),
+        duration,
+        requested_by,
+    }
+}
+
+pub async fn export_queue(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    options: &[CommandDataOption],
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+    let manager = songbird::get(ctx).await.unwrap();
+    let call = manager.get(guild_id).unwrap();
+
+    let handler = call.lock().await;
+    let tracks = handler.queue().current_queue();
+    drop(handler);
+
+    verify(!tracks.is_empty(), ParrotError::QueueEmpty)?;
+
+    let format = match options.first().and_then(|option| option.value.as_ref()) {
+        Some(value) if value.as_str() == Some("m3u") => QueueFormat::M3u,
+        _ => QueueFormat::Json,
+    };
+
+    let mut entries = vec![];
+    for track in &tracks {
+        entries.extend(QueueEntry::from_track(track).await);
+    }
+
+    let contents = write_queue(&entries, format)?;
+    let filename = format!("queue-{}.{}", guild_id, format.extension());
+
+    let mut embed = CreateEmbed::default();
+    embed.description(format!(
+        "{} **{}** tracks!",
+        tr(QUEUE_EXPORTED),
+        entries.len()
+    ));
+
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message.add_embed(embed).add_file(AttachmentType::Bytes {
+                        data: Cow::Owned(contents.into_bytes()),
+                        filename,
+                    })
+                })
+        })
+        .await?;
+
+    Ok(())
+}
+
+pub async fn import_queue(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    options: &[CommandDataOption],
+) -> Result<(), ParrotError> {
+    let attachment = options
+        .first()
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| interaction.data.resolved.attachments.get(&AttachmentId(id)))
+        .cloned()
+        .ok_or(ParrotError::Message(QUEUE_IMPORT_INVALID))?;
+
+    verify(
+        attachment.size <= MAX_IMPORT_SIZE,
+        ParrotError::Message(QUEUE_IMPORT_TOO_LARGE),
+    )?;
+
+    let format = QueueFormat::from_filename(&attachment.filename)
+        .ok_or(ParrotError::Message(QUEUE_IMPORT_INVALID))?;
+
+    let guild_id = interaction.guild_id.unwrap();
+    let manager = songbird::get(ctx).await.unwrap();
+
+    // try to join a voice channel if not in one just yet
+    summon(ctx, interaction, false).await?;
+    let call = manager.get(guild_id).unwrap();
+
+    let options = EnqueueOptions::new(ctx, interaction).await;
+
+    // fetching every track takes a while, reply right away like /play does
+    create_response_music(&ctx.http, interaction, ParrotMusicMessage::Search).await?;
+
+    let contents = attachment.download().await?;
+    let contents =
+        String::from_utf8(contents).map_err(|_| ParrotError::Message(QUEUE_IMPORT_INVALID))?;
+    let entries = read_queue(&contents, format)?;
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    // a file could point anywhere, so hold it to the same sources /play allows
+    let (mut entries, mut skipped): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| {
+        Url::parse(&entry.url)
+            .map(|url| guild_settings.is_source_allowed(&url))
+            .unwrap_or(false)
+    });
+
+    if entries.len() > MAX_IMPORT_TRACKS {
+        skipped.extend(entries.split_off(MAX_IMPORT_TRACKS));
+    }
+    let default_volume = guild_settings.default_volume;
+    drop(data);
+
+    let mut imported = 0;
+    for entry in entries {
+        let query_type = QueryType::VideoLink(entry.url.clone());
+
+        match enqueue_track(&call, &query_type, &options).await {
+            Ok(queue) => {
+                imported += 1;
+                update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
+            }
+            Err(err) => {
+                tracing::error!(err = ?err, url = %entry.url, "Failed to enqueue track");
+                skipped.push(entry);
+            }
+        }
+    }
+
+    let handler = call.lock().await;
+    let queue = handler.queue().current_queue();
+    drop(handler);
+
+    for track in &queue {
+        track.set_volume(default_volume).ok();
+    }
+
+    prefetch_queue(&queue);
+
+    edit_response_music(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::QueueImported {
+            imported,
+            skipped: skipped.len(),
+        },
+    )
+    .await?;
+
+    Ok(())
+}
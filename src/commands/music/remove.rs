@@ -1,21 +1,64 @@
-use std::cmp::min;
+use std::{cmp::min, collections::HashSet, sync::Arc, time::Duration};
 
 use serenity::{
-    builder::CreateEmbed, client::Context,
-    model::application::interaction::application_command::ApplicationCommandInteraction,
+    builder::{CreateComponents, CreateEmbed},
+    client::Context,
+    futures::StreamExt,
+    json::Value,
+    model::{
+        application::{
+            component::ButtonStyle,
+            interaction::{
+                application_command::{ApplicationCommandInteraction, CommandDataOption},
+                InteractionResponseType,
+            },
+        },
+        id::{GuildId, UserId},
+    },
+    prelude::Mutex,
 };
-use songbird::tracks::TrackHandle;
+use songbird::{tracks::TrackHandle, Call};
 
 use crate::{
+    commands::{autocomplete::resolve_track, play::RequestedBy},
     errors::{verify, ParrotError},
     handlers::track_end::update_queue_messages,
+    messaging::locale::tr,
     messaging::message::ParrotMusicMessage,
-    messaging::messages::REMOVED_QUEUE,
+    messaging::messages::{
+        FAIL_NO_SONG_ON_INDEX, REMOVED_QUEUE, REMOVE_CANCEL, REMOVE_CANCELLED, REMOVE_CONFIRM,
+        REMOVE_CONFIRM_PROMPT, REMOVE_CONFIRM_TRACKS, REMOVE_NOTHING_MATCHED, REMOVE_NO_FILTER,
+    },
     metrics,
     utils::create_embed_response,
     utils::create_response_music,
 };
 
+/// Removing more tracks than this at once asks for a confirmation first.
+const CONFIRM_THRESHOLD: usize = 3;
+const CONFIRM_TIMEOUT: u64 = 60;
+
+const CONFIRM_BUTTON_ID: &str = "remove_confirm";
+const CANCEL_BUTTON_ID: &str = "remove_cancel";
+
+/// Picks queued tracks by who requested them or by their title.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemoveFilter {
+    User(UserId),
+    Match(String),
+}
+
+impl RemoveFilter {
+    pub fn matches(&self, requester: Option<UserId>, title: Option<&str>) -> bool {
+        match self {
+            Self::User(user_id) => requester == Some(*user_id),
+            Self::Match(text) => title
+                .map(|title| title.to_lowercase().contains(&text.to_lowercase()))
+                .unwrap_or(false),
+        }
+    }
+}
+
 pub async fn remove(
     ctx: &Context,
     interaction: &mut ApplicationCommandInteraction,
@@ -28,23 +71,44 @@ pub async fn remove(
 
     let args = interaction.data.options.clone();
 
-    let remove_index = args
-        .first()
-        .unwrap()
-        .value
-        .as_ref()
-        .unwrap()
-        .as_u64()
-        .unwrap() as usize;
-
-    let remove_until = match args.get(1) {
-        Some(arg) => arg.value.as_ref().unwrap().as_u64().unwrap() as usize,
-        None => remove_index,
+    let user = find_arg(&args, "user")
+        .and_then(Value::as_str)
+        .and_then(|id| id.parse::<u64>().ok())
+        .map(UserId);
+    let mine = find_arg(&args, "mine").and_then(Value::as_bool);
+    let text = find_arg(&args, "match").and_then(Value::as_str);
+
+    let filter = match (user, mine, text) {
+        (Some(user_id), _, _) => Some(RemoveFilter::User(user_id)),
+        (_, Some(true), _) => Some(RemoveFilter::User(interaction.user.id)),
+        (_, _, Some(text)) => Some(RemoveFilter::Match(text.to_string())),
+        _ => None,
     };
 
+    if let Some(filter) = filter {
+        return remove_filtered(ctx, interaction, call, guild_id, filter).await;
+    }
+
+    let index = find_arg(&args, "index")
+        .and_then(Value::as_str)
+        .ok_or(ParrotError::Message(REMOVE_NO_FILTER))?;
+
     let handler = call.lock().await;
     let queue = handler.queue().current_queue();
 
+    let remove_index = verify(
+        resolve_track(&queue, index).filter(|index| *index > 0),
+        ParrotError::Message(FAIL_NO_SONG_ON_INDEX),
+    )?;
+
+    let remove_until = match find_arg(&args, "until").and_then(Value::as_str) {
+        Some(until) => verify(
+            resolve_track(&queue, until),
+            ParrotError::Message(FAIL_NO_SONG_ON_INDEX),
+        )?,
+        None => remove_index,
+    };
+
     let queue_len = queue.len();
     let remove_until = min(remove_until, queue_len.saturating_sub(1));
 
@@ -84,12 +148,156 @@ pub async fn remove(
     Ok(())
 }
 
+async fn remove_filtered(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    call: Arc<Mutex<Call>>,
+    guild_id: GuildId,
+    filter: RemoveFilter,
+) -> Result<(), ParrotError> {
+    let handler = call.lock().await;
+    let queue = handler.queue().current_queue();
+    drop(handler);
+
+    verify(queue.len() > 1, ParrotError::QueueEmpty)?;
+
+    // the track that's playing is left alone, like removing by index does
+    let mut matched = vec![];
+    for track in queue.iter().skip(1) {
+        let requester = track.typemap().read().await.get::<RequestedBy>().copied();
+
+        if filter.matches(requester, track.metadata().title.as_deref()) {
+            matched.push(track.clone());
+        }
+    }
+
+    verify(
+        !matched.is_empty(),
+        ParrotError::Message(REMOVE_NOTHING_MATCHED),
+    )?;
+
+    if matched.len() <= CONFIRM_THRESHOLD {
+        let queue = remove_tracks(&call, &matched).await;
+
+        if let [track] = matched.as_slice() {
+            let embed = create_remove_enqueued_embed(track).await;
+            create_embed_response(&ctx.http, interaction, embed).await?;
+        } else {
+            create_response_music(&ctx.http, interaction, ParrotMusicMessage::RemoveMultiple)
+                .await?;
+        }
+
+        update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
+        return Ok(());
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed.description(format!(
+        "{} **{}** {}",
+        tr(REMOVE_CONFIRM_PROMPT),
+        matched.len(),
+        tr(REMOVE_CONFIRM_TRACKS)
+    ));
+
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message.add_embed(embed).components(build_confirm_btns)
+                })
+        })
+        .await?;
+
+    let message = interaction.get_interaction_response(&ctx.http).await?;
+
+    let mci = message
+        .await_component_interaction(ctx)
+        .author_id(interaction.user.id)
+        .timeout(Duration::from_secs(CONFIRM_TIMEOUT))
+        .await;
+
+    let confirmed = matches!(&mci, Some(mci) if mci.data.custom_id == CONFIRM_BUTTON_ID);
+
+    let mut embed = CreateEmbed::default();
+    if confirmed {
+        let queue = remove_tracks(&call, &matched).await;
+        update_queue_messages(&ctx.http, &ctx.data, &queue, guild_id).await;
+
+        embed.description(ParrotMusicMessage::RemoveMultiple);
+    } else {
+        embed.description(tr(REMOVE_CANCELLED));
+    }
+
+    match mci {
+        Some(mci) => {
+            mci.create_interaction_response(&ctx, |r| {
+                r.kind(InteractionResponseType::UpdateMessage);
+                r.interaction_response_data(|d| d.set_embed(embed).components(|c| c))
+            })
+            .await?;
+        }
+        None => {
+            interaction
+                .edit_original_interaction_response(&ctx.http, |edit| {
+                    edit.set_embed(embed).components(|c| c)
+                })
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the tracks wherever they are now, the queue may have moved on since they were picked.
+async fn remove_tracks(call: &Arc<Mutex<Call>>, tracks: &[TrackHandle]) -> Vec<TrackHandle> {
+    let uuids: HashSet<_> = tracks.iter().map(TrackHandle::uuid).collect();
+
+    let handler = call.lock().await;
+    handler.queue().modify_queue(|queue| {
+        let mut idx = 1;
+        while idx < queue.len() {
+            if !uuids.contains(&queue[idx].uuid()) {
+                idx += 1;
+            } else if let Some(track) = queue.remove(idx) {
+                track.stop().ok();
+            }
+        }
+    });
+
+    handler.queue().current_queue()
+}
+
+fn build_confirm_btns(components: &mut CreateComponents) -> &mut CreateComponents {
+    components.create_action_row(|action_row| {
+        action_row
+            .create_button(|button| {
+                button
+                    .custom_id(CONFIRM_BUTTON_ID)
+                    .label(tr(REMOVE_CONFIRM))
+                    .style(ButtonStyle::Danger)
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(CANCEL_BUTTON_ID)
+                    .label(tr(REMOVE_CANCEL))
+                    .style(ButtonStyle::Secondary)
+            })
+    })
+}
+
+fn find_arg<'a>(args: &'a [CommandDataOption], name: &str) -> Option<&'a Value> {
+    args.iter()
+        .find(|arg| arg.name == name)
+        .and_then(|arg| arg.value.as_ref())
+}
+
 async fn create_remove_enqueued_embed(track: &TrackHandle) -> CreateEmbed {
     let mut embed = CreateEmbed::default();
     let metadata = track.metadata().clone();
 
     embed.field(
-        REMOVED_QUEUE,
+        tr(REMOVED_QUEUE),
         &format!(
             "[**{}**]({})",
             metadata.title.unwrap(),
@@ -36,6 +36,6 @@ pub async fn repeat(
         Ok(_) if !was_looping => {
             create_response_music(&ctx.http, interaction, ParrotMusicMessage::LoopEnable).await
         }
-        _ => Err(ParrotError::Other(FAIL_LOOP)),
+        _ => Err(ParrotError::Message(FAIL_LOOP)),
     }
 }
@@ -1,57 +1,209 @@
 use std::time::Duration;
 
 use serenity::{
-    client::Context,
+    client::Context, json::Value,
     model::application::interaction::application_command::ApplicationCommandInteraction,
 };
+use songbird::tracks::TrackHandle;
 
 use crate::{
     errors::{verify, ParrotError},
     messaging::message::ParrotMusicMessage,
-    messaging::messages::{FAIL_MINUTES_PARSING, FAIL_SECONDS_PARSING},
+    messaging::messages::FAIL_TIMESTAMP_PARSING,
     metrics,
-    utils::create_response_music,
+    sources::metadata_cache::{Chapter, METADATA_CACHE},
+    utils::{create_response_music, get_human_readable_timestamp, parse_timestamp},
 };
 
+/// How far `/forward` and `/rewind` move when the track has no chapters.
+const DEFAULT_SEEK_STEP: Duration = Duration::from_secs(10);
+
+/// Rewinding this close to the start of a chapter goes to the previous one instead.
+const CHAPTER_REWIND_GRACE: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeekTarget {
+    Absolute(Duration),
+    Forward(Duration),
+    Backward(Duration),
+}
+
+impl SeekTarget {
+    /// Parses an absolute timestamp, or a relative one when prefixed with `+` or `-`.
+    pub fn parse(input: &str) -> Option<SeekTarget> {
+        let input = input.trim();
+
+        if let Some(offset) = input.strip_prefix('+') {
+            return parse_timestamp(offset).map(Self::Forward);
+        }
+
+        if let Some(offset) = input.strip_prefix('-') {
+            return parse_timestamp(offset).map(Self::Backward);
+        }
+
+        parse_timestamp(input).map(Self::Absolute)
+    }
+
+    /// Position to seek to, kept within the bounds of the track.
+    pub fn resolve(&self, position: Duration, duration: Option<Duration>) -> Duration {
+        let target = match self {
+            Self::Absolute(timestamp) => *timestamp,
+            Self::Forward(offset) => position.saturating_add(*offset),
+            Self::Backward(offset) => position.saturating_sub(*offset),
+        };
+
+        match duration {
+            Some(duration) => target.min(duration),
+            None => target,
+        }
+    }
+}
+
 pub async fn seek(
     ctx: &Context,
     interaction: &mut ApplicationCommandInteraction,
 ) -> Result<(), ParrotError> {
     let _timer = metrics::record_command(ctx, "seek");
 
-    let guild_id = interaction.guild_id.unwrap();
-    let manager = songbird::get(ctx).await.unwrap();
-    let call = manager.get(guild_id).unwrap();
-
     let args = interaction.data.options.clone();
     let seek_time = args.first().unwrap().value.as_ref().unwrap();
 
-    let timestamp_str = seek_time.as_str().unwrap();
-    let mut units_iter = timestamp_str.split(':');
+    let target = SeekTarget::parse(seek_time.as_str().unwrap());
+    let target = verify(target, ParrotError::Message(FAIL_TIMESTAMP_PARSING))?;
 
-    let minutes = units_iter.next().and_then(|c| c.parse::<u64>().ok());
-    let minutes = verify(minutes, ParrotError::Other(FAIL_MINUTES_PARSING))?;
+    let track = current_track(ctx, interaction).await?;
+    seek_track(ctx, interaction, &track, target, None).await
+}
+
+pub async fn forward(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "forward");
+
+    let track = current_track(ctx, interaction).await?;
+    let position = track_position(&track).await?;
 
-    let seconds = units_iter.next().and_then(|c| c.parse::<u64>().ok());
-    let seconds = verify(seconds, ParrotError::Other(FAIL_SECONDS_PARSING))?;
+    let (target, chapter) = match seconds_arg(interaction) {
+        Some(seconds) => (SeekTarget::Forward(seconds), None),
+        None => match next_chapter(&track_chapters(&track), position) {
+            Some(chapter) => (SeekTarget::Absolute(chapter.start()), Some(chapter)),
+            None => (SeekTarget::Forward(DEFAULT_SEEK_STEP), None),
+        },
+    };
 
-    let timestamp = minutes * 60 + seconds;
+    seek_track(ctx, interaction, &track, target, chapter).await
+}
+
+pub async fn rewind(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "rewind");
+
+    let track = current_track(ctx, interaction).await?;
+    let position = track_position(&track).await?;
+
+    let (target, chapter) = match seconds_arg(interaction) {
+        Some(seconds) => (SeekTarget::Backward(seconds), None),
+        None => match previous_chapter(&track_chapters(&track), position) {
+            Some(chapter) => (SeekTarget::Absolute(chapter.start()), Some(chapter)),
+            None => (SeekTarget::Backward(DEFAULT_SEEK_STEP), None),
+        },
+    };
+
+    seek_track(ctx, interaction, &track, target, chapter).await
+}
+
+async fn seek_track(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    track: &TrackHandle,
+    target: SeekTarget,
+    chapter: Option<Chapter>,
+) -> Result<(), ParrotError> {
+    let position = track_position(track).await?;
+    let timestamp = target.resolve(position, track.metadata().duration);
+
+    track
+        .seek_time(timestamp)
+        .map_err(|_| ParrotError::NothingPlaying)?;
+
+    let message = match chapter {
+        Some(chapter) => ParrotMusicMessage::SeekChapter {
+            title: chapter.title,
+        },
+        None => ParrotMusicMessage::Seek {
+            timestamp: get_human_readable_timestamp(Some(timestamp)),
+        },
+    };
+
+    create_response_music(&ctx.http, interaction, message).await
+}
+
+async fn current_track(
+    ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+) -> Result<TrackHandle, ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+    let manager = songbird::get(ctx).await.unwrap();
+    let call = manager.get(guild_id).unwrap();
 
     let handler = call.lock().await;
-    let track = handler
-        .queue()
-        .current()
-        .ok_or(ParrotError::NothingPlaying)?;
+    let track = handler.queue().current();
     drop(handler);
 
-    track.seek_time(Duration::from_secs(timestamp)).unwrap();
+    track.ok_or(ParrotError::NothingPlaying)
+}
 
-    create_response_music(
-        &ctx.http,
-        interaction,
-        ParrotMusicMessage::Seek {
-            timestamp: timestamp_str.to_owned(),
-        },
-    )
-    .await
+async fn track_position(track: &TrackHandle) -> Result<Duration, ParrotError> {
+    track
+        .get_info()
+        .await
+        .map(|info| info.position)
+        .map_err(|_| ParrotError::NothingPlaying)
+}
+
+fn seconds_arg(interaction: &ApplicationCommandInteraction) -> Option<Duration> {
+    interaction
+        .data
+        .options
+        .first()
+        .and_then(|arg| arg.value.as_ref())
+        .and_then(Value::as_u64)
+        .map(Duration::from_secs)
+}
+
+/// Chapters of the track, as found by yt-dlp when the track was queued.
+pub fn track_chapters(track: &TrackHandle) -> Vec<Chapter> {
+    track
+        .metadata()
+        .source_url
+        .as_deref()
+        .and_then(|url| METADATA_CACHE.get(url))
+        .map(|metadata| metadata.chapters)
+        .unwrap_or_default()
+}
+
+/// The first chapter that starts after the position.
+pub fn next_chapter(chapters: &[Chapter], position: Duration) -> Option<Chapter> {
+    chapters
+        .iter()
+        .find(|chapter| chapter.start() > position)
+        .cloned()
+}
+
+/// The start of the chapter being played, or of the previous one when it has just started,
+/// like the previous button of a media player.
+pub fn previous_chapter(chapters: &[Chapter], position: Duration) -> Option<Chapter> {
+    let current = chapters
+        .iter()
+        .rposition(|chapter| chapter.start() <= position)?;
+
+    let elapsed = position.saturating_sub(chapters[current].start());
+    if elapsed < CHAPTER_REWIND_GRACE && current > 0 {
+        return chapters.get(current - 1).cloned();
+    }
+
+    chapters.get(current).cloned()
 }
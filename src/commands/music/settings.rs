@@ -0,0 +1,1128 @@
+use std::{borrow::Cow, time::Duration};
+
+use serenity::{
+    builder::{CreateComponents, CreateEmbed, CreateInputText},
+    client::Context,
+    futures::StreamExt,
+    http::AttachmentType,
+    json::Value,
+    model::{
+        application::{
+            component::{ActionRow, ActionRowComponent, ButtonStyle, InputTextStyle},
+            interaction::{
+                application_command::{ApplicationCommandInteraction, CommandDataOption},
+                InteractionResponseType,
+            },
+        },
+        id::{AttachmentId, ChannelId, GuildId},
+    },
+};
+
+use crate::{
+    commands::{
+        channels::format_channels,
+        crossfade::MAX_CROSSFADE_SECS,
+        manage_sources::{apply_domain_form, create_domain_form, create_invalid_rules_embed},
+    },
+    errors::{verify, ParrotError},
+    guild::{
+        filter::{parse_list, ContentFilter},
+        migrations::{upgrade, CURRENT_SETTINGS_VERSION},
+        rules::SourceRule,
+        settings::{GuildSettings, GuildSettingsMap},
+        theme::{format_color, parse_color, Theme, MAX_TEMPLATE_LEN},
+        visibility::ResponseKind,
+    },
+    messaging::locale::{available_locales, tr, translate},
+    messaging::message::ParrotMusicMessage,
+    messaging::messages::{
+        CHANNELS_COMMANDS, CHANNELS_VOICE, DOMAIN_FORM_TITLE, LANGUAGE_NAME, SETTINGS_ACCENT_COLOR,
+        SETTINGS_ACCENT_COLOR_PLACEHOLDER, SETTINGS_AGE_RESTRICTED,
+        SETTINGS_AGE_RESTRICTED_REJECTED, SETTINGS_ALLOWED, SETTINGS_ANY, SETTINGS_AUTOPAUSE,
+        SETTINGS_BLOCKED_WORDS, SETTINGS_CATEGORIES, SETTINGS_COMPACT, SETTINGS_CROSSFADE,
+        SETTINGS_CROSSFADE_PLACEHOLDER, SETTINGS_DEFAULT, SETTINGS_DUPLICATES,
+        SETTINGS_DUPLICATES_REJECTED, SETTINGS_EXPORTED, SETTINGS_FILTER_CATEGORIES_PLACEHOLDER,
+        SETTINGS_FILTER_TITLE, SETTINGS_FILTER_WORDS_PLACEHOLDER, SETTINGS_IMPORTED,
+        SETTINGS_IMPORT_CANCEL, SETTINGS_IMPORT_CANCELLED, SETTINGS_IMPORT_CONFIRM,
+        SETTINGS_IMPORT_INVALID, SETTINGS_IMPORT_PROMPT, SETTINGS_IMPORT_TOO_LARGE,
+        SETTINGS_IMPORT_UNCHANGED, SETTINGS_LANGUAGE, SETTINGS_LANGUAGE_USER, SETTINGS_NONE,
+        SETTINGS_NOW_PLAYING_TEMPLATE, SETTINGS_NOW_PLAYING_TEMPLATE_PLACEHOLDER, SETTINGS_OFF,
+        SETTINGS_ON, SETTINGS_PUBLIC_RESPONSES, SETTINGS_REJECTED, SETTINGS_RESPONSES_INVALID,
+        SETTINGS_SOURCES, SETTINGS_THEME, SETTINGS_THEME_INVALID, SETTINGS_THUMBNAILS,
+        SETTINGS_TITLE, SETTINGS_TRACK_QUEUED_TEMPLATE, SETTINGS_TRACK_QUEUED_TEMPLATE_PLACEHOLDER,
+        SETTINGS_VOLUME, SETTINGS_VOLUME_PLACEHOLDER,
+    },
+    metrics,
+    utils::{
+        create_response_ephemeral, create_response_music_ephemeral, is_ephemeral, truncate,
+        EMBED_FIELD_MAX_LEN,
+    },
+};
+
+const SETTINGS_TIMEOUT: u64 = 600;
+
+const AUTOPAUSE_ID: &str = "settings_autopause";
+const DUPLICATES_ID: &str = "settings_duplicates";
+const SOURCES_ID: &str = "settings_sources";
+const SOURCES_FORM_ID: &str = "settings_sources_form";
+const AGE_RESTRICTED_ID: &str = "settings_age_restricted";
+const FILTER_ID: &str = "settings_filter";
+const FILTER_FORM_ID: &str = "settings_filter_form";
+const VOLUME_ID: &str = "settings_volume";
+const CROSSFADE_ID: &str = "settings_crossfade";
+const LANGUAGE_ID: &str = "settings_language";
+const THUMBNAILS_ID: &str = "settings_thumbnails";
+const COMPACT_ID: &str = "settings_compact";
+const THEME_ID: &str = "settings_theme";
+const THEME_FORM_ID: &str = "settings_theme_form";
+
+/// Value of the language option that follows each member's own language.
+const LANGUAGE_USER: &str = "user";
+
+const VOLUME_CHOICES: [u64; 10] = [10, 20, 30, 40, 50, 60, 70, 80, 90, 100];
+const CROSSFADE_CHOICES: [u64; 7] = [0, 2, 4, 6, 8, 10, 12];
+
+const IMPORT_CONFIRM_ID: &str = "settings_import_confirm";
+const IMPORT_CANCEL_ID: &str = "settings_import_cancel";
+const IMPORT_CONFIRM_TIMEOUT: u64 = 60;
+
+/// Exported settings are tiny, anything much larger isn't one.
+const MAX_IMPORT_SIZE: u64 = 64 * 1024;
+
+pub async fn settings(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+) -> Result<(), ParrotError> {
+    let _timer = metrics::record_command(ctx, "settings");
+
+    let ephemeral = is_ephemeral(ctx, interaction.guild_id, ResponseKind::Settings).await;
+
+    let args = interaction.data.options.clone();
+    let Some(subcommand) = args.first() else {
+        return show_settings(ctx, interaction, ephemeral).await;
+    };
+
+    match subcommand.name.as_str() {
+        "export" => export_settings(ctx, interaction, ephemeral).await,
+        "import" => import_settings(ctx, interaction, &subcommand.options, ephemeral).await,
+        "reset" => reset_settings(ctx, interaction, ephemeral).await,
+        "responses" => set_responses(ctx, interaction, &subcommand.options, ephemeral).await,
+        _ => show_settings(ctx, interaction, ephemeral).await,
+    }
+}
+
+async fn export_settings(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    let contents = serde_json::to_string_pretty(guild_settings)?;
+    drop(data);
+
+    let filename = format!("settings-{}.json", guild_id);
+
+    let mut embed = CreateEmbed::default();
+    embed.description(tr(SETTINGS_EXPORTED));
+
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message
+                        .add_embed(embed)
+                        .add_file(AttachmentType::Bytes {
+                            data: Cow::Owned(contents.into_bytes()),
+                            filename,
+                        })
+                        .ephemeral(ephemeral)
+                })
+        })
+        .await?;
+
+    Ok(())
+}
+
+async fn import_settings(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    options: &[CommandDataOption],
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let attachment = options
+        .first()
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| interaction.data.resolved.attachments.get(&AttachmentId(id)))
+        .cloned()
+        .ok_or(ParrotError::Message(SETTINGS_IMPORT_INVALID))?;
+
+    verify(
+        attachment.size <= MAX_IMPORT_SIZE,
+        ParrotError::Message(SETTINGS_IMPORT_TOO_LARGE),
+    )?;
+
+    let contents = attachment.download().await?;
+    let contents =
+        String::from_utf8(contents).map_err(|_| ParrotError::Message(SETTINGS_IMPORT_INVALID))?;
+    let mut imported = parse_settings_file(&contents, guild_id)?;
+
+    // channels of another server would keep the bot from being used anywhere here
+    let in_guild = |channel_id: &ChannelId| {
+        ctx.cache
+            .guild_channel(*channel_id)
+            .map_or(false, |channel| channel.guild_id == guild_id)
+    };
+    imported.command_channels.retain(in_guild);
+    imported.voice_channels.retain(in_guild);
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    let changes = diff_settings(guild_settings, &imported);
+    drop(data);
+
+    if changes.is_empty() {
+        return create_response_ephemeral(
+            &ctx.http,
+            interaction,
+            tr(SETTINGS_IMPORT_UNCHANGED),
+            ephemeral,
+        )
+        .await;
+    }
+
+    let mut embed = CreateEmbed::default();
+    embed.description(tr(SETTINGS_IMPORT_PROMPT));
+    // both values share a field, so each gets half of it
+    let max_len = (EMBED_FIELD_MAX_LEN - ">>> \n→ ".chars().count()) / 2;
+    for (name, before, after) in changes {
+        let before = truncate(&before, max_len);
+        let after = truncate(&after, max_len);
+        embed.field(name, format!(">>> {}\n→ {}", before, after), false);
+    }
+
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message
+                        .add_embed(embed)
+                        .components(build_import_confirm_btns)
+                        .ephemeral(ephemeral)
+                })
+        })
+        .await?;
+
+    let message = interaction.get_interaction_response(&ctx.http).await?;
+
+    let mci = message
+        .await_component_interaction(ctx)
+        .author_id(interaction.user.id)
+        .timeout(Duration::from_secs(IMPORT_CONFIRM_TIMEOUT))
+        .await;
+
+    let confirmed = matches!(&mci, Some(mci) if mci.data.custom_id == IMPORT_CONFIRM_ID);
+
+    let mut embed = CreateEmbed::default();
+    if confirmed {
+        imported.save()?;
+
+        let mut data = ctx.data.write().await;
+        let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+        settings.insert(guild_id, imported);
+        drop(data);
+
+        embed.description(tr(SETTINGS_IMPORTED));
+    } else {
+        embed.description(tr(SETTINGS_IMPORT_CANCELLED));
+    }
+
+    match mci {
+        Some(mci) => {
+            mci.create_interaction_response(&ctx, |r| {
+                r.kind(InteractionResponseType::UpdateMessage);
+                r.interaction_response_data(|d| d.set_embed(embed).components(|c| c))
+            })
+            .await?;
+        }
+        None => {
+            interaction
+                .edit_original_interaction_response(&ctx.http, |edit| {
+                    edit.set_embed(embed).components(|c| c)
+                })
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn reset_settings(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = GuildSettings::new(guild_id);
+    guild_settings.save()?;
+    settings.insert(guild_id, guild_settings);
+    drop(data);
+
+    create_response_music_ephemeral(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::SettingsReset,
+        ephemeral,
+    )
+    .await
+}
+
+async fn set_responses(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    options: &[CommandDataOption],
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let option = |name: &str| {
+        options
+            .iter()
+            .find(|option| option.name == name)
+            .and_then(|option| option.value.clone())
+    };
+
+    let kind = option("kind")
+        .and_then(|kind| kind.as_str().and_then(ResponseKind::from_id))
+        .ok_or(ParrotError::Message(SETTINGS_RESPONSES_INVALID))?;
+    let public = option("public")
+        .and_then(|public| public.as_bool())
+        .ok_or(ParrotError::Message(SETTINGS_RESPONSES_INVALID))?;
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+    guild_settings.set_response_public(kind, public);
+    guild_settings.save()?;
+    drop(data);
+
+    create_response_music_ephemeral(
+        &ctx.http,
+        interaction,
+        ParrotMusicMessage::Responses { kind, public },
+        ephemeral,
+    )
+    .await
+}
+
+async fn show_settings(
+    ctx: &Context,
+    interaction: &mut ApplicationCommandInteraction,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let guild_id = interaction.guild_id.unwrap();
+
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    let embed = create_settings_embed(guild_settings);
+    let components = create_settings_components(guild_settings);
+    drop(data);
+
+    interaction
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message
+                        .add_embed(embed)
+                        .set_components(components)
+                        .ephemeral(ephemeral)
+                })
+        })
+        .await?;
+
+    let message = interaction.get_interaction_response(&ctx.http).await?;
+
+    // only whoever opened the settings can change them through this message
+    let mut cib = message
+        .await_component_interactions(ctx)
+        .author_id(interaction.user.id)
+        .timeout(Duration::from_secs(SETTINGS_TIMEOUT))
+        .build();
+
+    while let Some(mci) = cib.next().await {
+        let selected = mci
+            .data
+            .values
+            .first()
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if mci.data.custom_id == SOURCES_ID {
+            let mut data = ctx.data.write().await;
+            let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+            let guild_settings = settings
+                .entry(guild_id)
+                .or_insert_with(|| GuildSettings::new(guild_id));
+
+            let form = create_domain_form(guild_settings);
+            drop(data);
+
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::Modal);
+                r.interaction_response_data(|d| {
+                    d.title(tr(DOMAIN_FORM_TITLE));
+                    d.custom_id(SOURCES_FORM_ID);
+                    d.set_components(form)
+                })
+            })
+            .await?;
+
+            let Some(modal) = message
+                .await_modal_interaction(ctx)
+                .author_id(interaction.user.id)
+                .filter(|modal| modal.data.custom_id == SOURCES_FORM_ID)
+                .timeout(Duration::from_secs(SETTINGS_TIMEOUT))
+                .await
+            else {
+                continue;
+            };
+
+            let mut data = ctx.data.write().await;
+            let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+            let guild_settings = settings
+                .entry(guild_id)
+                .or_insert_with(|| GuildSettings::new(guild_id));
+
+            let applied = apply_domain_form(guild_settings, &modal.data.components);
+            if applied.is_ok() {
+                guild_settings.save()?;
+            }
+
+            let embed = create_settings_embed(guild_settings);
+            let components = create_settings_components(guild_settings);
+            drop(data);
+
+            modal
+                .create_interaction_response(&ctx.http, |r| match &applied {
+                    Ok(()) => r
+                        .kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|d| {
+                            d.add_embed(embed).set_components(components)
+                        }),
+                    Err(invalid) => r
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|d| {
+                            d.add_embed(create_invalid_rules_embed(invalid))
+                                .ephemeral(true)
+                        }),
+                })
+                .await?;
+
+            continue;
+        }
+
+        if mci.data.custom_id == THEME_ID {
+            let mut data = ctx.data.write().await;
+            let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+            let guild_settings = settings
+                .entry(guild_id)
+                .or_insert_with(|| GuildSettings::new(guild_id));
+
+            let form = create_theme_form(&guild_settings.theme);
+            drop(data);
+
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::Modal);
+                r.interaction_response_data(|d| {
+                    d.title(tr(SETTINGS_THEME));
+                    d.custom_id(THEME_FORM_ID);
+                    d.set_components(form)
+                })
+            })
+            .await?;
+
+            let Some(modal) = message
+                .await_modal_interaction(ctx)
+                .author_id(interaction.user.id)
+                .filter(|modal| modal.data.custom_id == THEME_FORM_ID)
+                .timeout(Duration::from_secs(SETTINGS_TIMEOUT))
+                .await
+            else {
+                continue;
+            };
+
+            let mut data = ctx.data.write().await;
+            let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+            let guild_settings = settings
+                .entry(guild_id)
+                .or_insert_with(|| GuildSettings::new(guild_id));
+
+            let theme = parse_theme_form(&guild_settings.theme, &modal.data.components);
+            if let Some(theme) = &theme {
+                guild_settings.set_theme(theme.clone());
+                guild_settings.save()?;
+            }
+
+            let embed = create_settings_embed(guild_settings);
+            let components = create_settings_components(guild_settings);
+            drop(data);
+
+            let mut invalid = CreateEmbed::default();
+            invalid.description(tr(SETTINGS_THEME_INVALID));
+
+            modal
+                .create_interaction_response(&ctx.http, |r| match &theme {
+                    Some(_) => r
+                        .kind(InteractionResponseType::UpdateMessage)
+                        .interaction_response_data(|d| {
+                            d.add_embed(embed).set_components(components)
+                        }),
+                    None => r
+                        .kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|d| d.add_embed(invalid).ephemeral(true)),
+                })
+                .await?;
+
+            continue;
+        }
+
+        if mci.data.custom_id == FILTER_ID {
+            let mut data = ctx.data.write().await;
+            let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+            let guild_settings = settings
+                .entry(guild_id)
+                .or_insert_with(|| GuildSettings::new(guild_id));
+
+            let form = create_filter_form(&guild_settings.content_filter);
+            drop(data);
+
+            mci.create_interaction_response(&ctx.http, |r| {
+                r.kind(InteractionResponseType::Modal);
+                r.interaction_response_data(|d| {
+                    d.title(tr(SETTINGS_FILTER_TITLE));
+                    d.custom_id(FILTER_FORM_ID);
+                    d.set_components(form)
+                })
+            })
+            .await?;
+
+            let Some(modal) = message
+                .await_modal_interaction(ctx)
+                .author_id(interaction.user.id)
+                .filter(|modal| modal.data.custom_id == FILTER_FORM_ID)
+                .timeout(Duration::from_secs(SETTINGS_TIMEOUT))
+                .await
+            else {
+                continue;
+            };
+
+            let (embed, components) = update_settings(ctx, guild_id, |guild_settings| {
+                apply_filter_form(guild_settings, &modal.data.components)
+            })
+            .await?;
+
+            modal
+                .create_interaction_response(&ctx.http, |r| {
+                    r.kind(InteractionResponseType::UpdateMessage);
+                    r.interaction_response_data(|d| d.add_embed(embed).set_components(components))
+                })
+                .await?;
+
+            continue;
+        }
+
+        let (embed, components) = update_settings(ctx, guild_id, |guild_settings| {
+            match (mci.data.custom_id.as_str(), selected) {
+                (AUTOPAUSE_ID, _) => guild_settings.toggle_autopause(),
+                (DUPLICATES_ID, _) => {
+                    guild_settings.set_no_duplicates(!guild_settings.no_duplicates)
+                }
+                (VOLUME_ID, Some(volume)) => {
+                    guild_settings.set_default_volume(volume as f32 / 100.0)
+                }
+                (CROSSFADE_ID, Some(seconds)) => guild_settings.set_crossfade(seconds),
+                (THUMBNAILS_ID, _) => guild_settings.set_theme(Theme {
+                    thumbnails: !guild_settings.theme.thumbnails,
+                    ..guild_settings.theme.clone()
+                }),
+                (COMPACT_ID, _) => guild_settings.set_theme(Theme {
+                    compact: !guild_settings.theme.compact,
+                    ..guild_settings.theme.clone()
+                }),
+                (LANGUAGE_ID, _) => guild_settings.set_language(
+                    mci.data
+                        .values
+                        .first()
+                        .filter(|language| *language != LANGUAGE_USER)
+                        .cloned(),
+                ),
+                (AGE_RESTRICTED_ID, _) => guild_settings.set_content_filter(ContentFilter {
+                    reject_age_restricted: !guild_settings.content_filter.reject_age_restricted,
+                    ..guild_settings.content_filter.clone()
+                }),
+                _ => {}
+            }
+        })
+        .await?;
+
+        mci.create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage);
+            r.interaction_response_data(|d| d.add_embed(embed).set_components(components))
+        })
+        .await?;
+    }
+
+    // the message may only be shown to whoever ran the command, which only the
+    // interaction itself can edit
+    interaction
+        .edit_original_interaction_response(&ctx.http, |edit| edit.components(|f| f))
+        .await
+        .ok();
+
+    Ok(())
+}
+
+/// Applies a change to the guild's settings and saves them, returning the refreshed message.
+async fn update_settings(
+    ctx: &Context,
+    guild_id: GuildId,
+    change: impl FnOnce(&mut GuildSettings),
+) -> Result<(CreateEmbed, CreateComponents), ParrotError> {
+    let mut data = ctx.data.write().await;
+    let settings = data.get_mut::<GuildSettingsMap>().unwrap();
+
+    let guild_settings = settings
+        .entry(guild_id)
+        .or_insert_with(|| GuildSettings::new(guild_id));
+
+    change(guild_settings);
+    guild_settings.save()?;
+
+    Ok((
+        create_settings_embed(guild_settings),
+        create_settings_components(guild_settings),
+    ))
+}
+
+pub fn create_settings_embed(guild_settings: &GuildSettings) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed.title(tr(SETTINGS_TITLE));
+
+    // long lists of words or rules are cut short, Discord rejects the whole embed otherwise
+    for (name, value, inline) in describe_settings(guild_settings) {
+        let value = format!(">>> {}", value);
+        embed.field(name, truncate(&value, EMBED_FIELD_MAX_LEN), inline);
+    }
+
+    embed
+}
+
+/// The settings as they're shown to users, in order, along with whether they fit inline.
+fn describe_settings(guild_settings: &GuildSettings) -> Vec<(&'static str, String, bool)> {
+    let autopause = match guild_settings.autopause {
+        true => tr(SETTINGS_ON),
+        false => tr(SETTINGS_OFF),
+    };
+
+    let volume = format!("{}%", (guild_settings.default_volume * 100.0).round());
+
+    let crossfade = match guild_settings.crossfade {
+        0 => tr(SETTINGS_OFF).to_string(),
+        seconds => format!("{}s", seconds),
+    };
+
+    let duplicates = match guild_settings.no_duplicates {
+        true => tr(SETTINGS_REJECTED),
+        false => tr(SETTINGS_ALLOWED),
+    };
+
+    let content_filter = &guild_settings.content_filter;
+    let age_restricted = match content_filter.reject_age_restricted {
+        true => tr(SETTINGS_REJECTED),
+        false => tr(SETTINGS_ALLOWED),
+    };
+
+    let categories = match content_filter.allowed_categories.is_empty() {
+        true => tr(SETTINGS_ANY).to_string(),
+        false => format_list(&content_filter.allowed_categories),
+    };
+
+    let language = match &guild_settings.language {
+        Some(locale) => format!("{} ({})", translate(locale, LANGUAGE_NAME), locale),
+        None => tr(SETTINGS_LANGUAGE_USER).to_string(),
+    };
+
+    let theme = &guild_settings.theme;
+    let accent_color = match theme.accent_color {
+        Some(color) => format_color(color),
+        None => tr(SETTINGS_DEFAULT).to_string(),
+    };
+
+    let on_off = |enabled: bool| match enabled {
+        true => tr(SETTINGS_ON).to_string(),
+        false => tr(SETTINGS_OFF).to_string(),
+    };
+
+    let template = |template: &Option<String>| match template {
+        Some(template) => format!("`{}`", template),
+        None => tr(SETTINGS_DEFAULT).to_string(),
+    };
+
+    let public_responses = match guild_settings.public_responses.is_empty() {
+        true => tr(SETTINGS_NONE).to_string(),
+        false => guild_settings
+            .public_responses
+            .iter()
+            .map(ResponseKind::description)
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    vec![
+        (tr(SETTINGS_AUTOPAUSE), autopause.to_string(), true),
+        (tr(SETTINGS_VOLUME), volume, true),
+        (tr(SETTINGS_CROSSFADE), crossfade, true),
+        (tr(SETTINGS_DUPLICATES), duplicates.to_string(), true),
+        (tr(SETTINGS_LANGUAGE), language, true),
+        (
+            tr(SETTINGS_AGE_RESTRICTED),
+            age_restricted.to_string(),
+            true,
+        ),
+        (
+            tr(SETTINGS_BLOCKED_WORDS),
+            format_list(&content_filter.blocked_words),
+            false,
+        ),
+        (tr(SETTINGS_CATEGORIES), categories, false),
+        (
+            tr(SETTINGS_SOURCES),
+            format_rules(&guild_settings.source_rules),
+            false,
+        ),
+        (
+            tr(CHANNELS_COMMANDS),
+            format_channels(&guild_settings.command_channels),
+            false,
+        ),
+        (
+            tr(CHANNELS_VOICE),
+            format_channels(&guild_settings.voice_channels),
+            false,
+        ),
+        (tr(SETTINGS_ACCENT_COLOR), accent_color, true),
+        (tr(SETTINGS_THUMBNAILS), on_off(theme.thumbnails), true),
+        (tr(SETTINGS_COMPACT), on_off(theme.compact), true),
+        (
+            tr(SETTINGS_NOW_PLAYING_TEMPLATE),
+            template(&theme.now_playing_template),
+            false,
+        ),
+        (
+            tr(SETTINGS_TRACK_QUEUED_TEMPLATE),
+            template(&theme.track_queued_template),
+            false,
+        ),
+        (tr(SETTINGS_PUBLIC_RESPONSES), public_responses, false),
+    ]
+}
+
+/// The settings that would change, with their current and new values.
+pub fn diff_settings(
+    current: &GuildSettings,
+    imported: &GuildSettings,
+) -> Vec<(&'static str, String, String)> {
+    describe_settings(current)
+        .into_iter()
+        .zip(describe_settings(imported))
+        .filter(|((_, before, _), (_, after, _))| before != after)
+        .map(|((name, before, _), (_, after, _))| (name, before, after))
+        .collect()
+}
+
+/// Reads settings made by `/settings export`, anything that isn't part of them or that
+/// couldn't be set through the bot is rejected.
+pub fn parse_settings_file(
+    contents: &str,
+    guild_id: GuildId,
+) -> Result<GuildSettings, ParrotError> {
+    let mut value: Value = serde_json::from_str(contents)
+        .map_err(|_| ParrotError::Message(SETTINGS_IMPORT_INVALID))?;
+
+    // settings from a newer version could hold anything
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    verify(
+        version <= CURRENT_SETTINGS_VERSION as u64,
+        ParrotError::Message(SETTINGS_IMPORT_INVALID),
+    )?;
+
+    // older exports are brought up to date first, so only what's left is checked
+    upgrade(&mut value);
+
+    let known = serde_json::to_value(GuildSettings::default())?;
+    let (Some(fields), Some(known)) = (value.as_object(), known.as_object()) else {
+        return Err(ParrotError::Message(SETTINGS_IMPORT_INVALID));
+    };
+
+    verify(
+        fields.keys().all(|key| known.contains_key(key)),
+        ParrotError::Message(SETTINGS_IMPORT_INVALID),
+    )?;
+
+    let mut settings: GuildSettings =
+        serde_json::from_value(value).map_err(|_| ParrotError::Message(SETTINGS_IMPORT_INVALID))?;
+
+    let known_language = settings
+        .language
+        .as_deref()
+        .map_or(true, |language| available_locales().contains(&language));
+
+    let theme = &settings.theme;
+    let valid_theme = theme.accent_color.map_or(true, |color| color <= 0xFFFFFF)
+        && [&theme.now_playing_template, &theme.track_queued_template]
+            .iter()
+            .filter_map(|template| template.as_deref())
+            .all(|template| template.chars().count() <= MAX_TEMPLATE_LEN);
+
+    verify(
+        (0.0..=1.0).contains(&settings.default_volume)
+            && settings.crossfade <= MAX_CROSSFADE_SECS as u64
+            && known_language
+            && valid_theme,
+        ParrotError::Message(SETTINGS_IMPORT_INVALID),
+    )?;
+
+    settings.guild_id = guild_id;
+
+    let rules = std::mem::take(&mut settings.source_rules);
+    settings.set_source_rules(rules);
+
+    Ok(settings)
+}
+
+fn format_rules(rules: &[SourceRule]) -> String {
+    match rules.is_empty() {
+        true => tr(SETTINGS_NONE).to_string(),
+        false => rules
+            .iter()
+            .map(|rule| format!("`{}`", rule))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+fn format_list(entries: &[String]) -> String {
+    match entries.is_empty() {
+        true => tr(SETTINGS_NONE).to_string(),
+        false => entries
+            .iter()
+            .map(|entry| format!("`{}`", entry))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// The inputs of the form that edits the content filter, filled with the current lists.
+fn create_filter_form(content_filter: &ContentFilter) -> CreateComponents {
+    let mut words_input = CreateInputText::default();
+    words_input
+        .label(tr(SETTINGS_BLOCKED_WORDS))
+        .custom_id("blocked_words")
+        .style(InputTextStyle::Paragraph)
+        .placeholder(tr(SETTINGS_FILTER_WORDS_PLACEHOLDER))
+        .value(content_filter.blocked_words.join("\n"))
+        .required(false);
+
+    let mut categories_input = CreateInputText::default();
+    categories_input
+        .label(tr(SETTINGS_CATEGORIES))
+        .custom_id("allowed_categories")
+        .style(InputTextStyle::Short)
+        .placeholder(tr(SETTINGS_FILTER_CATEGORIES_PLACEHOLDER))
+        .value(content_filter.allowed_categories.join(", "))
+        .required(false);
+
+    let mut components = CreateComponents::default();
+    components
+        .create_action_row(|r| r.add_input_text(words_input))
+        .create_action_row(|r| r.add_input_text(categories_input));
+
+    components
+}
+
+fn apply_filter_form(guild_settings: &mut GuildSettings, rows: &[ActionRow]) {
+    let mut content_filter = guild_settings.content_filter.clone();
+
+    for input in rows.iter().flat_map(|r| r.components.iter()) {
+        if let ActionRowComponent::InputText(it) = input {
+            match it.custom_id.as_str() {
+                "blocked_words" => content_filter.blocked_words = parse_list(&it.value),
+                "allowed_categories" => content_filter.allowed_categories = parse_list(&it.value),
+                _ => {}
+            }
+        }
+    }
+
+    guild_settings.set_content_filter(content_filter);
+}
+
+fn create_theme_form(theme: &Theme) -> CreateComponents {
+    let mut color_input = CreateInputText::default();
+    color_input
+        .label(tr(SETTINGS_ACCENT_COLOR))
+        .custom_id("accent_color")
+        .style(InputTextStyle::Short)
+        .placeholder(tr(SETTINGS_ACCENT_COLOR_PLACEHOLDER))
+        .value(theme.accent_color.map(format_color).unwrap_or_default())
+        .required(false);
+
+    let mut now_playing_input = CreateInputText::default();
+    now_playing_input
+        .label(tr(SETTINGS_NOW_PLAYING_TEMPLATE))
+        .custom_id("now_playing_template")
+        .style(InputTextStyle::Paragraph)
+        .placeholder(tr(SETTINGS_NOW_PLAYING_TEMPLATE_PLACEHOLDER))
+        .value(theme.now_playing_template.clone().unwrap_or_default())
+        .max_length(MAX_TEMPLATE_LEN as u64)
+        .required(false);
+
+    let mut track_queued_input = CreateInputText::default();
+    track_queued_input
+        .label(tr(SETTINGS_TRACK_QUEUED_TEMPLATE))
+        .custom_id("track_queued_template")
+        .style(InputTextStyle::Paragraph)
+        .placeholder(tr(SETTINGS_TRACK_QUEUED_TEMPLATE_PLACEHOLDER))
+        .value(theme.track_queued_template.clone().unwrap_or_default())
+        .max_length(MAX_TEMPLATE_LEN as u64)
+        .required(false);
+
+    let mut components = CreateComponents::default();
+    components
+        .create_action_row(|r| r.add_input_text(color_input))
+        .create_action_row(|r| r.add_input_text(now_playing_input))
+        .create_action_row(|r| r.add_input_text(track_queued_input));
+
+    components
+}
+
+/// Reads the submitted theme form on top of the current theme, nothing when it's invalid.
+fn parse_theme_form(theme: &Theme, rows: &[ActionRow]) -> Option<Theme> {
+    let mut theme = theme.clone();
+
+    let template = |value: &str| match value.trim() {
+        "" => Some(None),
+        template if template.chars().count() <= MAX_TEMPLATE_LEN => {
+            Some(Some(template.to_string()))
+        }
+        _ => None,
+    };
+
+    for input in rows.iter().flat_map(|r| r.components.iter()) {
+        if let ActionRowComponent::InputText(it) = input {
+            match it.custom_id.as_str() {
+                "accent_color" => {
+                    theme.accent_color = match it.value.trim() {
+                        "" => None,
+                        color => Some(parse_color(color)?),
+                    }
+                }
+                "now_playing_template" => theme.now_playing_template = template(&it.value)?,
+                "track_queued_template" => theme.track_queued_template = template(&it.value)?,
+                _ => {}
+            }
+        }
+    }
+
+    Some(theme)
+}
+
+fn create_settings_components(guild_settings: &GuildSettings) -> CreateComponents {
+    let volume = (guild_settings.default_volume * 100.0).round() as u64;
+
+    let mut components = CreateComponents::default();
+    components
+        .create_action_row(|row| {
+            row.create_button(|button| {
+                button
+                    .custom_id(AUTOPAUSE_ID)
+                    .label(tr(SETTINGS_AUTOPAUSE))
+                    .style(toggle_style(guild_settings.autopause))
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(DUPLICATES_ID)
+                    .label(tr(SETTINGS_DUPLICATES_REJECTED))
+                    .style(toggle_style(guild_settings.no_duplicates))
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(SOURCES_ID)
+                    .label(tr(DOMAIN_FORM_TITLE))
+                    .style(ButtonStyle::Secondary)
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(AGE_RESTRICTED_ID)
+                    .label(tr(SETTINGS_AGE_RESTRICTED_REJECTED))
+                    .style(toggle_style(
+                        guild_settings.content_filter.reject_age_restricted,
+                    ))
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(FILTER_ID)
+                    .label(tr(SETTINGS_FILTER_TITLE))
+                    .style(ButtonStyle::Secondary)
+            })
+        })
+        .create_action_row(|row| {
+            row.create_select_menu(|menu| {
+                menu.custom_id(VOLUME_ID)
+                    .placeholder(tr(SETTINGS_VOLUME_PLACEHOLDER))
+                    .options(|options| {
+                        for choice in VOLUME_CHOICES {
+                            options.create_option(|option| {
+                                option
+                                    .label(format!("{}%", choice))
+                                    .value(choice)
+                                    .default_selection(choice == volume)
+                            });
+                        }
+                        options
+                    })
+            })
+        })
+        .create_action_row(|row| {
+            row.create_select_menu(|menu| {
+                menu.custom_id(CROSSFADE_ID)
+                    .placeholder(tr(SETTINGS_CROSSFADE_PLACEHOLDER))
+                    .options(|options| {
+                        for choice in CROSSFADE_CHOICES {
+                            let label = match choice {
+                                0 => tr(SETTINGS_OFF).to_string(),
+                                seconds => format!("{}s", seconds),
+                            };
+
+                            options.create_option(|option| {
+                                option
+                                    .label(label)
+                                    .value(choice)
+                                    .default_selection(choice == guild_settings.crossfade)
+                            });
+                        }
+                        options
+                    })
+            })
+        })
+        .create_action_row(|row| {
+            row.create_button(|button| {
+                button
+                    .custom_id(THUMBNAILS_ID)
+                    .label(tr(SETTINGS_THUMBNAILS))
+                    .style(toggle_style(guild_settings.theme.thumbnails))
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(COMPACT_ID)
+                    .label(tr(SETTINGS_COMPACT))
+                    .style(toggle_style(guild_settings.theme.compact))
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(THEME_ID)
+                    .label(tr(SETTINGS_THEME))
+                    .style(ButtonStyle::Secondary)
+            })
+        })
+        .create_action_row(|row| {
+            row.create_select_menu(|menu| {
+                menu.custom_id(LANGUAGE_ID)
+                    .placeholder(tr(SETTINGS_LANGUAGE))
+                    .options(|options| {
+                        options.create_option(|option| {
+                            option
+                                .label(tr(SETTINGS_LANGUAGE_USER))
+                                .value(LANGUAGE_USER)
+                                .default_selection(guild_settings.language.is_none())
+                        });
+                        for locale in available_locales() {
+                            options.create_option(|option| {
+                                option
+                                    .label(format!(
+                                        "{} ({})",
+                                        translate(locale, LANGUAGE_NAME),
+                                        locale
+                                    ))
+                                    .value(locale)
+                                    .default_selection(
+                                        guild_settings.language.as_deref() == Some(locale),
+                                    )
+                            });
+                        }
+                        options
+                    })
+            })
+        });
+
+    components
+}
+
+fn toggle_style(enabled: bool) -> ButtonStyle {
+    match enabled {
+        true => ButtonStyle::Success,
+        false => ButtonStyle::Secondary,
+    }
+}
+
+fn build_import_confirm_btns(components: &mut CreateComponents) -> &mut CreateComponents {
+    components.create_action_row(|action_row| {
+        action_row
+            .create_button(|button| {
+                button
+                    .custom_id(IMPORT_CONFIRM_ID)
+                    .label(tr(SETTINGS_IMPORT_CONFIRM))
+                    .style(ButtonStyle::Primary)
+            })
+            .create_button(|button| {
+                button
+                    .custom_id(IMPORT_CANCEL_ID)
+                    .label(tr(SETTINGS_IMPORT_CANCEL))
+                    .style(ButtonStyle::Secondary)
+            })
+    })
+}
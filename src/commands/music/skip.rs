@@ -8,8 +8,10 @@ use songbird::{tracks::TrackHandle, Call};
 use tokio::sync::MutexGuard;
 
 use crate::{
+    commands::autocomplete::resolve_track,
     errors::{verify, ParrotError},
     messaging::message::ParrotMusicMessage,
+    messaging::messages::FAIL_NO_SONG_ON_INDEX,
     metrics,
     utils::create_response_music,
 };
@@ -24,17 +26,23 @@ pub async fn skip(
     let manager = songbird::get(ctx).await.unwrap();
     let call = manager.get(guild_id).unwrap();
 
-    let args = interaction.data.options.clone();
-    let to_skip = match args.first() {
-        Some(arg) => arg.value.as_ref().unwrap().as_u64().unwrap() as usize,
-        None => 1,
-    };
-
     let handler = call.lock().await;
     let queue = handler.queue();
 
     verify(!queue.is_empty(), ParrotError::NothingPlaying)?;
 
+    let args = interaction.data.options.clone();
+    let to_skip = match args.first().and_then(|arg| arg.value.as_ref()) {
+        Some(value) => {
+            let to_skip = resolve_track(&queue.current_queue(), value.as_str().unwrap());
+            verify(
+                to_skip.filter(|to_skip| *to_skip > 0),
+                ParrotError::Message(FAIL_NO_SONG_ON_INDEX),
+            )?
+        }
+        None => 1,
+    };
+
     let tracks_to_skip = min(to_skip, queue.len());
 
     handler.queue().modify_queue(|v| {
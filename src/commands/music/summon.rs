@@ -12,7 +12,7 @@ use songbird::{Event, TrackEvent};
 use crate::{
     connection::get_voice_channel_for_user,
     errors::ParrotError,
-    handlers::{IdleHandler, TrackEndHandler},
+    handlers::{CrossfadeHandler, IdleHandler, TrackEndHandler},
     messaging::message::ParrotMusicMessage,
     metrics,
     utils::create_response_music,
@@ -58,6 +58,7 @@ pub async fn summon(
                 http: ctx.http.clone(),
                 manager,
                 interaction: interaction.clone(),
+                ctx_data: ctx.data.clone(),
                 limit: 60 * 10,
                 count: Default::default(),
             },
@@ -71,6 +72,16 @@ pub async fn summon(
                 ctx_data: ctx.data.clone(),
             },
         );
+
+        handler.add_global_event(
+            Event::Periodic(Duration::from_millis(250), None),
+            CrossfadeHandler {
+                guild_id: guild.id,
+                call: call.clone(),
+                ctx_data: ctx.data.clone(),
+                state: Default::default(),
+            },
+        );
     }
 
     if send_reply {
@@ -4,8 +4,11 @@ use serenity::{
 };
 
 use crate::{
-    errors::ParrotError, messaging::message::ParrotMusicMessage, metrics,
-    utils::create_response_music,
+    errors::ParrotError,
+    guild::visibility::ResponseKind,
+    messaging::message::ParrotMusicMessage,
+    metrics,
+    utils::{create_response_music_ephemeral, is_ephemeral},
 };
 
 pub async fn version(
@@ -15,12 +18,15 @@ pub async fn version(
     let _timer = metrics::record_command(ctx, "version");
 
     let current = option_env!("CARGO_PKG_VERSION").unwrap_or_else(|| "Unknown");
-    create_response_music(
+    let ephemeral = is_ephemeral(ctx, interaction.guild_id, ResponseKind::Version).await;
+
+    create_response_music_ephemeral(
         &ctx.http,
         interaction,
         ParrotMusicMessage::Version {
             current: current.to_owned(),
         },
+        ephemeral,
     )
     .await
 }
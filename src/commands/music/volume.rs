@@ -59,7 +59,7 @@ async fn update_volume(
     let manager = songbird::get(ctx).await.unwrap();
     let call = manager.get(guild_id).unwrap();
 
-    let volume = verify(volume, ParrotError::Other(FAIL_VOLUME_PARSING))?;
+    let volume = verify(volume, ParrotError::Message(FAIL_VOLUME_PARSING))?;
 
     let adjusted_volume = volume / 100.0;
 
@@ -0,0 +1,195 @@
+use std::{env, error::Error, fmt::Display, io::Error as IoError, process::Stdio};
+
+use lazy_static::lazy_static;
+use tokio::process::Command as TokioCommand;
+
+const DEFAULT_YTDL_PATH: &str = "yt-dlp";
+const DEFAULT_YTDL_FORMAT: &str = "webm[abr>0]/bestaudio/best";
+const DEFAULT_FFMPEG_PATH: &str = "ffmpeg";
+
+/// Oldest releases known to work, older ones fail to extract YouTube or lack flags we pass.
+const MIN_YTDL_VERSION: [u32; 3] = [2023, 3, 4];
+const MIN_FFMPEG_VERSION: [u32; 2] = [4, 0];
+
+lazy_static! {
+    pub static ref YTDL: YtdlConfig = YtdlConfig::from_env();
+    pub static ref FFMPEG: FfmpegConfig = FfmpegConfig::from_env();
+}
+
+/// How yt-dlp is invoked, configured through the `YTDL_*` environment variables.
+pub struct YtdlConfig {
+    pub path: String,
+    /// Format selector used to pick the audio stream of a track.
+    pub format: String,
+    /// Arguments appended to every invocation, e.g. cookies or a proxy.
+    pub args: Vec<String>,
+}
+
+impl YtdlConfig {
+    fn from_env() -> YtdlConfig {
+        let mut args = vec![];
+
+        if let Ok(cookies) = env::var("YTDL_COOKIES") {
+            args.extend(["--cookies".to_string(), cookies]);
+        }
+
+        if let Ok(proxy) = env::var("YTDL_PROXY") {
+            args.extend(["--proxy".to_string(), proxy]);
+        }
+
+        if let Ok(rate_limit) = env::var("YTDL_RATE_LIMIT") {
+            args.extend(["--limit-rate".to_string(), rate_limit]);
+        }
+
+        if env_flag("YTDL_GEO_BYPASS") {
+            args.push("--geo-bypass".to_string());
+        }
+
+        args.extend(env_args("YTDL_EXTRA_ARGS"));
+
+        YtdlConfig {
+            path: env::var("YTDL_PATH").unwrap_or(DEFAULT_YTDL_PATH.to_string()),
+            format: env::var("YTDL_FORMAT").unwrap_or(DEFAULT_YTDL_FORMAT.to_string()),
+            args,
+        }
+    }
+}
+
+/// How ffmpeg is invoked, configured through the `FFMPEG_*` environment variables.
+pub struct FfmpegConfig {
+    pub path: String,
+    /// Output arguments applied to every track, e.g. an audio filter.
+    pub args: Vec<String>,
+    /// Whether Opus streams may skip decoding, which is only possible without filters.
+    pub passthrough: bool,
+}
+
+impl FfmpegConfig {
+    fn from_env() -> FfmpegConfig {
+        let args = env_args("FFMPEG_ARGS");
+        let passthrough = args.is_empty() && !env_flag_disabled("OPUS_PASSTHROUGH");
+
+        FfmpegConfig {
+            path: env::var("FFMPEG_PATH").unwrap_or(DEFAULT_FFMPEG_PATH.to_string()),
+            args,
+            passthrough,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ToolError {
+    /// The binary couldn't be run at all.
+    Missing(&'static str, String, IoError),
+    /// The binary ran but is older than the minimum supported release.
+    Outdated(&'static str, String, String),
+}
+
+impl Error for ToolError {}
+
+impl Display for ToolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(tool, path, err) => f.write_str(&format!(
+                "Fatality! Could not run {tool} at `{path}` ({err}), make sure it's installed or point to it with the {} variable",
+                path_variable(tool)
+            )),
+            Self::Outdated(tool, found, required) => f.write_str(&format!(
+                "Fatality! {tool} {found} is too old, at least {required} is required"
+            )),
+        }
+    }
+}
+
+/// Checks that yt-dlp and ffmpeg can be run and are recent enough, so a broken setup is
+/// reported on startup rather than as failing tracks.
+pub async fn validate() -> Result<(), ToolError> {
+    let ytdl_version = tool_version("yt-dlp", &YTDL.path, "--version").await?;
+    check_version("yt-dlp", &ytdl_version, &MIN_YTDL_VERSION)?;
+
+    // ffmpeg prints `ffmpeg version n6.0 Copyright (c) ...` on its first line
+    let ffmpeg_version = tool_version("ffmpeg", &FFMPEG.path, "-version").await?;
+    let ffmpeg_version = ffmpeg_version
+        .split_whitespace()
+        .nth(2)
+        .unwrap_or_default()
+        .to_string();
+    check_version("ffmpeg", &ffmpeg_version, &MIN_FFMPEG_VERSION)?;
+
+    tracing::info!(ytdl = %ytdl_version, ffmpeg = %ffmpeg_version, "Found external tools");
+    Ok(())
+}
+
+async fn tool_version(tool: &'static str, path: &str, flag: &str) -> Result<String, ToolError> {
+    let output = TokioCommand::new(path)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .map_err(|err| ToolError::Missing(tool, path.to_string(), err))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().next().unwrap_or_default().trim().to_string())
+}
+
+fn check_version(tool: &'static str, found: &str, required: &[u32]) -> Result<(), ToolError> {
+    // development builds (e.g. ffmpeg's `N-111234-g...`) carry no release number, trust them
+    let Some(version) = parse_version(found) else {
+        tracing::warn!(version = %found, "Could not determine the version of {}", tool);
+        return Ok(());
+    };
+
+    if version.as_slice() < required {
+        let required = required.iter().map(u32::to_string).collect::<Vec<_>>();
+        return Err(ToolError::Outdated(
+            tool,
+            found.to_string(),
+            required.join("."),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Parses the leading dotted release number of a version string, such as `2023.07.06`,
+/// `n6.0` or `4.4.2-0ubuntu0.22.04.1`.
+pub fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let version = version.trim_start_matches(['n', 'v']);
+    let release = version
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()?;
+
+    let parts = release
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<Vec<u32>, _>>()
+        .ok()?;
+
+    Some(parts)
+}
+
+fn path_variable(tool: &str) -> &'static str {
+    match tool {
+        "ffmpeg" => "FFMPEG_PATH",
+        _ => "YTDL_PATH",
+    }
+}
+
+fn env_flag(key: &str) -> bool {
+    env::var(key)
+        .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or_default()
+}
+
+fn env_flag_disabled(key: &str) -> bool {
+    env::var(key)
+        .map(|value| matches!(value.to_lowercase().as_str(), "0" | "false" | "no"))
+        .unwrap_or_default()
+}
+
+fn env_args(key: &str) -> Vec<String> {
+    env::var(key)
+        .map(|args| args.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
@@ -4,19 +4,26 @@ use std::{
 };
 
 use rspotify::ClientError as RSpotifyClientError;
+use rusqlite::Error as SqliteError;
 use serenity::{model::mention::Mention, prelude::SerenityError};
 use songbird::input::error::Error as InputError;
 
-use crate::messaging::messages::{
-    FAIL_ANOTHER_CHANNEL, FAIL_AUTHOR_DISCONNECTED, FAIL_AUTHOR_NOT_FOUND,
-    FAIL_NO_VOICE_CONNECTION, FAIL_WRONG_CHANNEL, NOTHING_IS_PLAYING, QUEUE_IS_EMPTY,
-    TRACK_INAPPROPRIATE, TRACK_NOT_FOUND,
+use crate::{
+    messaging::locale::{tr, Message},
+    messaging::messages::{
+        FAIL_ANOTHER_CHANNEL, FAIL_AUTHOR_DISCONNECTED, FAIL_AUTHOR_NOT_FOUND,
+        FAIL_COMMAND_CHANNEL, FAIL_NO_VOICE_CONNECTION, FAIL_VOICE_CHANNEL, FAIL_WRONG_CHANNEL,
+        NOTHING_IS_PLAYING, PLAYLIST_FAILED, PLAYLIST_INTERRUPTED, QUEUE_IS_EMPTY,
+        TRACK_INAPPROPRIATE, TRACK_NOT_FOUND, YTDL_MISSING,
+    },
+    sources::youtube::PlaylistError,
 };
 
 /// A common error enum returned by most of the crate's functions within a [`Result`].
 #[derive(Debug)]
 pub enum ParrotError {
     Other(&'static str),
+    Message(Message),
     QueueEmpty,
     NotInRange(&'static str, isize, isize, isize),
     NotConnected,
@@ -25,11 +32,15 @@ pub enum ParrotError {
     AuthorNotFound,
     NothingPlaying,
     TrackFail(InputError),
+    PlaylistFail(PlaylistError),
     AlreadyConnected(Mention),
+    CommandChannel(Vec<Mention>),
+    VoiceChannel(Vec<Mention>),
     Serenity(SerenityError),
     RSpotify(RSpotifyClientError),
     IO(std::io::Error),
     Serde(serde_json::Error),
+    Sqlite(SqliteError),
 }
 
 /// `ParrotError` implements the [`Debug`] and [`Display`] traits
@@ -43,41 +54,68 @@ impl Display for ParrotError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Other(msg) => f.write_str(msg),
-            Self::QueueEmpty => f.write_str(QUEUE_IS_EMPTY),
+            Self::Message(message) => f.write_str(tr(*message)),
+            Self::QueueEmpty => f.write_str(tr(QUEUE_IS_EMPTY)),
             Self::NotInRange(param, value, lower, upper) => f.write_str(&format!(
                 "`{param}` should be between {lower} and {upper} but was {value}"
             )),
-            Self::NotConnected => f.write_str(FAIL_NO_VOICE_CONNECTION),
+            Self::NotConnected => f.write_str(tr(FAIL_NO_VOICE_CONNECTION)),
             Self::AuthorDisconnected(mention) => {
-                f.write_fmt(format_args!("{} {}", FAIL_AUTHOR_DISCONNECTED, mention))
+                f.write_fmt(format_args!("{} {}", tr(FAIL_AUTHOR_DISCONNECTED), mention))
             }
-            Self::WrongVoiceChannel => f.write_str(FAIL_WRONG_CHANNEL),
-            Self::AuthorNotFound => f.write_str(FAIL_AUTHOR_NOT_FOUND),
+            Self::WrongVoiceChannel => f.write_str(tr(FAIL_WRONG_CHANNEL)),
+            Self::AuthorNotFound => f.write_str(tr(FAIL_AUTHOR_NOT_FOUND)),
             Self::AlreadyConnected(mention) => {
-                f.write_fmt(format_args!("{} {}", FAIL_ANOTHER_CHANNEL, mention))
+                f.write_fmt(format_args!("{} {}", tr(FAIL_ANOTHER_CHANNEL), mention))
             }
-            Self::NothingPlaying => f.write_str(NOTHING_IS_PLAYING),
+            Self::CommandChannel(channels) => f.write_fmt(format_args!(
+                "{} {}!",
+                tr(FAIL_COMMAND_CHANNEL),
+                mentions(channels)
+            )),
+            Self::VoiceChannel(channels) => f.write_fmt(format_args!(
+                "{} {}!",
+                tr(FAIL_VOICE_CHANNEL),
+                mentions(channels)
+            )),
+            Self::NothingPlaying => f.write_str(tr(NOTHING_IS_PLAYING)),
             Self::TrackFail(err) => match err {
                 InputError::Json {
                     error: _,
                     parsed_text,
                 } => {
                     if parsed_text.contains("Sign in to confirm your age") {
-                        f.write_str(TRACK_INAPPROPRIATE)
+                        f.write_str(tr(TRACK_INAPPROPRIATE))
                     } else {
-                        f.write_str(TRACK_NOT_FOUND)
+                        f.write_str(tr(TRACK_NOT_FOUND))
                     }
                 }
                 _ => f.write_str(&format!("{err}")),
             },
+            Self::PlaylistFail(err) => match err {
+                PlaylistError::Spawn(_) => f.write_str(tr(YTDL_MISSING)),
+                PlaylistError::Stdout | PlaylistError::Read(_) => {
+                    f.write_str(tr(PLAYLIST_INTERRUPTED))
+                }
+                PlaylistError::Exit(_) => f.write_str(tr(PLAYLIST_FAILED)),
+            },
             Self::Serenity(err) => f.write_str(&format!("{err}")),
             Self::RSpotify(err) => f.write_str(&format!("{err}")),
             Self::IO(err) => f.write_str(&format!("{err}")),
             Self::Serde(err) => f.write_str(&format!("{err}")),
+            Self::Sqlite(err) => f.write_str(&format!("{err}")),
         }
     }
 }
 
+fn mentions(channels: &[Mention]) -> String {
+    channels
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Implementation of the [`PartialEq`] trait for the [`ParrotError`] enum.
 /// For some enum variants, values are considered equal when their inner values
 /// are equal and for others when they are of the same type.
@@ -85,6 +123,7 @@ impl PartialEq for ParrotError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Other(l0), Self::Other(r0)) => l0 == r0,
+            (Self::Message(l0), Self::Message(r0)) => l0 == r0,
             (Self::NotInRange(l0, l1, l2, l3), Self::NotInRange(r0, r1, r2, r3)) => {
                 l0 == r0 && l1 == r1 && l2 == r2 && l3 == r3
             }
@@ -114,6 +153,13 @@ impl From<serde_json::Error> for ParrotError {
     }
 }
 
+/// Provides an implementation to convert a [`PlaylistError`] to a [`ParrotError`].
+impl From<PlaylistError> for ParrotError {
+    fn from(err: PlaylistError) -> Self {
+        Self::PlaylistFail(err)
+    }
+}
+
 /// Provides an implementation to convert a [`SerenityError`] to a [`ParrotError`].
 impl From<SerenityError> for ParrotError {
     fn from(err: SerenityError) -> Self {
@@ -134,6 +180,13 @@ impl From<RSpotifyClientError> for ParrotError {
     }
 }
 
+/// Provides an implementation to convert a rusqlite [`SqliteError`] to a [`ParrotError`].
+impl From<SqliteError> for ParrotError {
+    fn from(err: SqliteError) -> ParrotError {
+        ParrotError::Sqlite(err)
+    }
+}
+
 /// Types that implement this trait can be tested as true or false and also provide
 /// a way of unpacking themselves.
 pub trait Verifiable<T> {
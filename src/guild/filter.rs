@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sources::metadata_cache::CachedMetadata;
+
+/// What a guild keeps out of the queue, checked against what yt-dlp reports of a track.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct ContentFilter {
+    /// Rejects videos with an age limit, which only play at all when yt-dlp is signed in.
+    pub reject_age_restricted: bool,
+    /// Words or phrases that reject a track when its title contains them, ignoring casing.
+    pub blocked_words: Vec<String>,
+    /// When any are set, only tracks in one of these categories are queued, e.g. `Music`.
+    /// Tracks from sources that have no categories are let through.
+    pub allowed_categories: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterViolation {
+    AgeRestricted,
+    BlockedWord,
+    Category,
+}
+
+impl ContentFilter {
+    pub fn check(&self, metadata: &CachedMetadata) -> Result<(), FilterViolation> {
+        if self.reject_age_restricted && metadata.age_limit.unwrap_or_default() > 0 {
+            return Err(FilterViolation::AgeRestricted);
+        }
+
+        let title = metadata.title.as_deref().unwrap_or_default();
+        if self
+            .blocked_words
+            .iter()
+            .any(|word| contains_words(title, word))
+        {
+            return Err(FilterViolation::BlockedWord);
+        }
+
+        let in_category = metadata.categories.iter().any(|category| {
+            self.allowed_categories
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(category))
+        });
+
+        if !self.allowed_categories.is_empty() && !metadata.categories.is_empty() && !in_category {
+            return Err(FilterViolation::Category);
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads a list typed one entry per line or separated by commas.
+pub fn parse_list(list: &str) -> Vec<String> {
+    list.split(['\n', ','])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether the words appear in the text as whole words, so blocking `ass` leaves `class` be.
+fn contains_words(text: &str, words: &str) -> bool {
+    let words = normalize(words);
+    !words.is_empty() && format!(" {} ", normalize(text)).contains(&format!(" {} ", words))
+}
+
+fn normalize(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,102 @@
+use serde_json::{Map, Value};
+
+use crate::{
+    errors::ParrotError,
+    guild::{
+        rules::SourceRule,
+        settings::{normalize_domain, GuildSettings},
+    },
+};
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// Upgrades settings saved by older versions, the first one runs on settings saved before
+/// they were versioned. Migrations run in order starting from the version the settings were
+/// saved with, so new ones are only ever appended.
+const MIGRATIONS: &[Migration] = &[normalize_domains, domains_to_rules];
+
+/// The version settings are saved with, which is how many migrations they've gone through.
+pub const CURRENT_SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Brings saved settings up to date and deserializes them. Fields that are still missing
+/// afterwards take their defaults.
+pub fn migrate(mut value: Value) -> Result<GuildSettings, ParrotError> {
+    upgrade(&mut value);
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Runs the migrations the settings haven't gone through yet.
+pub fn upgrade(value: &mut Value) {
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+
+    if version > CURRENT_SETTINGS_VERSION as u64 {
+        tracing::warn!(
+            version = version,
+            "Settings were saved by a newer version, some of them may be lost"
+        );
+    }
+
+    if let Some(settings) = value.as_object_mut() {
+        for migration in MIGRATIONS.iter().skip(version as usize) {
+            migration(settings);
+        }
+
+        settings.insert(String::from("version"), CURRENT_SETTINGS_VERSION.into());
+    }
+}
+
+/// Version 1: domains were stored as typed, so ones with a scheme, a path or capitals
+/// never matched anything.
+fn normalize_domains(settings: &mut Map<String, Value>) {
+    for key in ["allowed_domains", "banned_domains"] {
+        let Some(Value::Array(domains)) = settings.get_mut(key) else {
+            continue;
+        };
+
+        let mut normalized: Vec<Value> = vec![];
+        for domain in domains
+            .iter()
+            .filter_map(Value::as_str)
+            .map(normalize_domain)
+        {
+            if !domain.is_empty() && !normalized.contains(&Value::from(domain.as_str())) {
+                normalized.push(domain.into());
+            }
+        }
+
+        *domains = normalized;
+    }
+}
+
+/// Version 2: the allowed and banned domains became ordered rules. Allowed domains only
+/// ever applied while no domain was banned, so only one of the lists is kept.
+fn domains_to_rules(settings: &mut Map<String, Value>) {
+    let mut take = |key: &str| match settings.remove(key) {
+        Some(Value::Array(domains)) => domains
+            .iter()
+            .filter_map(Value::as_str)
+            .map(String::from)
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    };
+
+    let allowed = take("allowed_domains");
+    let banned = take("banned_domains");
+
+    let (action, domains) = match banned.is_empty() {
+        true => ("allow", allowed),
+        false => ("ban", banned),
+    };
+
+    // a domain that can't be read as a rule would keep the settings from loading at all
+    let rules: Vec<Value> = domains
+        .iter()
+        .map(|domain| format!("{} {}", action, domain))
+        .filter(|rule| rule.parse::<SourceRule>().is_ok())
+        .map(Value::from)
+        .collect();
+
+    if !rules.is_empty() {
+        settings.insert(String::from("source_rules"), rules.into());
+    }
+}
@@ -1,2 +1,8 @@
 pub mod cache;
+pub mod filter;
+pub mod migrations;
+pub mod rules;
 pub mod settings;
+pub mod store;
+pub mod theme;
+pub mod visibility;
@@ -0,0 +1,217 @@
+use std::{fmt::Display, str::FromStr};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Keeps the expressions people type in from growing without bounds.
+const REGEX_SIZE_LIMIT: usize = 1 << 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleAction {
+    Allow,
+    Ban,
+}
+
+/// A rule deciding whether links may be played, written as `allow` or `ban` followed by
+/// a pattern, one of:
+/// - a host, which also covers its subdomains, e.g. `bandcamp.com`
+/// - a host with wildcards, e.g. `*.bandcamp.com` for its subdomains only
+/// - any of the above followed by a path the link must start with, wildcards included,
+///   e.g. `youtube.com/playlist?list=PL*`
+/// - a regular expression between slashes matched against the whole link,
+///   e.g. `/^https://www\.youtube\.com/@parrot/`
+///
+/// Rules are kept the way they were written so they can be shown and edited as such.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SourceRule {
+    pub action: RuleAction,
+    pub pattern: String,
+    matcher: Matcher,
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Url(Regex),
+    Host { host: Regex, path: Option<Regex> },
+}
+
+/// The rule as it was written, returned when it couldn't be read.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidRule(pub String);
+
+impl SourceRule {
+    pub fn allow(pattern: &str) -> Result<SourceRule, InvalidRule> {
+        Self::new(RuleAction::Allow, pattern)
+    }
+
+    pub fn ban(pattern: &str) -> Result<SourceRule, InvalidRule> {
+        Self::new(RuleAction::Ban, pattern)
+    }
+
+    fn new(action: RuleAction, pattern: &str) -> Result<SourceRule, InvalidRule> {
+        let pattern = pattern.trim();
+        let invalid = || InvalidRule(format!("{} {}", action, pattern));
+
+        let matcher = match pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+        {
+            Some(expression) => Matcher::Url(compile(expression).ok_or_else(invalid)?),
+            None => {
+                let pattern = pattern
+                    .split_once("://")
+                    .map(|(_, rest)| rest)
+                    .unwrap_or(pattern);
+
+                let (host, path) = match pattern.find('/') {
+                    Some(idx) => (&pattern[..idx], Some(&pattern[idx..])),
+                    None => (pattern, None),
+                };
+
+                let valid_host = host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '*'));
+                if host.is_empty() || !valid_host {
+                    return Err(invalid());
+                }
+
+                // a bare host covers its subdomains too, like domains always did
+                let host = match host.starts_with('*') {
+                    true => format!("^{}$", glob(&host.to_lowercase())),
+                    false => format!("^(.+\\.)?{}$", glob(&host.to_lowercase())),
+                };
+
+                Matcher::Host {
+                    host: compile(&host).ok_or_else(invalid)?,
+                    path: match path {
+                        Some(path) => {
+                            Some(compile(&format!("^{}", glob(path))).ok_or_else(invalid)?)
+                        }
+                        None => None,
+                    },
+                }
+            }
+        };
+
+        Ok(SourceRule {
+            action,
+            pattern: pattern.to_string(),
+            matcher,
+        })
+    }
+
+    pub fn matches(&self, url: &Url) -> bool {
+        match &self.matcher {
+            Matcher::Url(expression) => expression.is_match(url.as_str()),
+            Matcher::Host { host, path } => {
+                let Some(url_host) = url.host_str() else {
+                    return false;
+                };
+
+                let url_path = match url.query() {
+                    Some(query) => format!("{}?{}", url.path(), query),
+                    None => url.path().to_string(),
+                };
+
+                host.is_match(url_host)
+                    && path.as_ref().map_or(true, |path| path.is_match(&url_path))
+            }
+        }
+    }
+}
+
+impl FromStr for SourceRule {
+    type Err = InvalidRule;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+
+        match rule.split_once(char::is_whitespace) {
+            Some((action, pattern)) if action.eq_ignore_ascii_case("allow") => Self::allow(pattern),
+            Some((action, pattern)) if action.eq_ignore_ascii_case("ban") => Self::ban(pattern),
+            _ => Err(InvalidRule(rule.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for SourceRule {
+    type Error = String;
+
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        rule.parse().map_err(|InvalidRule(rule)| rule)
+    }
+}
+
+impl From<SourceRule> for String {
+    fn from(rule: SourceRule) -> Self {
+        rule.to_string()
+    }
+}
+
+impl PartialEq for SourceRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.action == other.action && self.pattern == other.pattern
+    }
+}
+
+impl Eq for SourceRule {}
+
+impl Display for RuleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => f.write_str("allow"),
+            Self::Ban => f.write_str("ban"),
+        }
+    }
+}
+
+impl Display for SourceRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.action, self.pattern)
+    }
+}
+
+/// Reads one rule per line, blank lines are skipped. Every line that isn't a rule is
+/// returned instead, so they can all be pointed out at once.
+pub fn parse_rules(rules: &str) -> Result<Vec<SourceRule>, Vec<String>> {
+    let (rules, invalid): (Vec<_>, Vec<_>) = rules
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(SourceRule::from_str)
+        .partition(Result::is_ok);
+
+    match invalid.is_empty() {
+        true => Ok(rules.into_iter().map(Result::unwrap).collect()),
+        false => Err(invalid
+            .into_iter()
+            .filter_map(Result::err)
+            .map(|InvalidRule(rule)| rule)
+            .collect()),
+    }
+}
+
+/// The first rule that matches the link decides. Links no rule matches are only allowed
+/// when every rule bans, so a list of bans works like the banned domains used to.
+pub fn is_source_allowed(rules: &[SourceRule], url: &Url) -> bool {
+    match rules.iter().find(|rule| rule.matches(url)) {
+        Some(rule) => rule.action == RuleAction::Allow,
+        None => rules.iter().all(|rule| rule.action == RuleAction::Ban),
+    }
+}
+
+fn glob(pattern: &str) -> String {
+    pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*")
+}
+
+fn compile(expression: &str) -> Option<Regex> {
+    RegexBuilder::new(expression)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .ok()
+}
@@ -1,120 +1,200 @@
-use std::{
-    collections::{HashMap, HashSet},
-    env,
-    fs::{create_dir_all, OpenOptions},
-    io::{BufReader, BufWriter},
-    path::Path,
-};
+use std::collections::HashMap;
 
-use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use serenity::{model::id::GuildId, prelude::TypeMapKey};
-
-use crate::errors::ParrotError;
+use serenity::{
+    model::id::{ChannelId, GuildId},
+    prelude::TypeMapKey,
+};
+use url::Url;
+
+use crate::{
+    errors::ParrotError,
+    guild::{
+        filter::ContentFilter,
+        migrations::CURRENT_SETTINGS_VERSION,
+        rules::{is_source_allowed, SourceRule},
+        store::settings_store,
+        theme::Theme,
+        visibility::ResponseKind,
+    },
+};
 
-const DEFAULT_SETTINGS_PATH: &str = "data/settings";
 const DEFAULT_ALLOWED_DOMAINS: [&str; 2] = ["youtube.com", "youtu.be"];
 const DEFAULT_VOLUME_LEVEL: f32 = 0.2;
 
-lazy_static! {
-    static ref SETTINGS_PATH: String =
-        env::var("SETTINGS_PATH").unwrap_or(DEFAULT_SETTINGS_PATH.to_string());
-}
-
+/// Fields missing from saved settings take their defaults, anything more involved is
+/// upgraded by a migration.
 #[derive(Deserialize, Serialize)]
+#[serde(default)]
 pub struct GuildSettings {
+    /// Version of the settings, settings saved before versioning count as version zero.
+    #[serde(default)]
+    pub version: u32,
     pub guild_id: GuildId,
     pub autopause: bool,
-    pub allowed_domains: HashSet<String>,
-    pub banned_domains: HashSet<String>,
+    /// Decide which links may be played, in order.
+    pub source_rules: Vec<SourceRule>,
     pub default_volume: f32,
+    /// Seconds the end of a track overlaps the start of the next one, zero disables it.
+    pub crossfade: u64,
+    /// Rejects tracks that are already in the queue.
+    pub no_duplicates: bool,
+    pub content_filter: ContentFilter,
+    /// Text channels music commands can be used in, any channel when empty.
+    pub command_channels: Vec<ChannelId>,
+    /// Voice channels the bot may join, any channel when empty.
+    pub voice_channels: Vec<ChannelId>,
+    /// Locale responses are written in, each member's own Discord language when unset.
+    pub language: Option<String>,
+    pub theme: Theme,
+    /// Responses shown to everyone, the rest only to whoever ran the command.
+    pub public_responses: Vec<ResponseKind>,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        GuildSettings::new(GuildId(0))
+    }
 }
 
 impl GuildSettings {
     pub fn new(guild_id: GuildId) -> GuildSettings {
-        let allowed_domains: HashSet<String> = DEFAULT_ALLOWED_DOMAINS
-            .iter()
-            .map(|d| d.to_string())
-            .collect();
-
         GuildSettings {
+            version: CURRENT_SETTINGS_VERSION,
             guild_id,
             autopause: false,
-            allowed_domains,
-            banned_domains: HashSet::new(),
+            source_rules: default_source_rules(),
             default_volume: DEFAULT_VOLUME_LEVEL,
+            crossfade: 0,
+            no_duplicates: false,
+            content_filter: ContentFilter::default(),
+            command_channels: vec![],
+            voice_channels: vec![],
+            language: None,
+            theme: Theme::default(),
+            public_responses: vec![],
         }
     }
 
     pub fn load_if_exists(&mut self) -> Result<(), ParrotError> {
-        let path = format!("{}/{}.json", SETTINGS_PATH.as_str(), self.guild_id);
-        if !Path::new(&path).exists() {
-            return Ok(());
+        if let Some(settings) = settings_store().load(self.guild_id)? {
+            *self = GuildSettings {
+                guild_id: self.guild_id,
+                ..settings
+            };
         }
-        self.load()
-    }
-
-    pub fn load(&mut self) -> Result<(), ParrotError> {
-        let path = format!("{}/{}.json", SETTINGS_PATH.as_str(), self.guild_id);
-        let file = OpenOptions::new().read(true).open(path)?;
-        let reader = BufReader::new(file);
-        *self = serde_json::from_reader::<_, GuildSettings>(reader)?;
         Ok(())
     }
 
     pub fn save(&self) -> Result<(), ParrotError> {
-        create_dir_all(SETTINGS_PATH.as_str())?;
-        let path = format!("{}/{}.json", SETTINGS_PATH.as_str(), self.guild_id);
-
-        let file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .create(true)
-            .open(path)?;
-
-        let writer = BufWriter::new(file);
-        serde_json::to_writer(writer, self)?;
-        Ok(())
+        settings_store().save(self)
     }
 
     pub fn toggle_autopause(&mut self) {
         self.autopause = !self.autopause;
     }
 
-    pub fn set_allowed_domains(&mut self, allowed_str: &str) {
-        let allowed = allowed_str
-            .split(';')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect();
+    /// Replaces the rules, with no rules at all only the default sources are allowed.
+    pub fn set_source_rules(&mut self, rules: Vec<SourceRule>) {
+        self.source_rules = match rules.is_empty() {
+            true => default_source_rules(),
+            false => rules,
+        };
+    }
+
+    pub fn is_source_allowed(&self, url: &Url) -> bool {
+        is_source_allowed(&self.source_rules, url)
+    }
+
+    pub fn set_default_volume(&mut self, volume: f32) {
+        self.default_volume = volume;
+    }
+
+    pub fn set_crossfade(&mut self, seconds: u64) {
+        self.crossfade = seconds;
+    }
 
-        self.allowed_domains = allowed;
+    pub fn set_no_duplicates(&mut self, no_duplicates: bool) {
+        self.no_duplicates = no_duplicates;
     }
 
-    pub fn set_banned_domains(&mut self, banned_str: &str) {
-        let banned = banned_str
-            .split(';')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string())
-            .collect();
+    pub fn set_content_filter(&mut self, content_filter: ContentFilter) {
+        self.content_filter = content_filter;
+    }
 
-        self.banned_domains = banned;
+    pub fn add_command_channel(&mut self, channel_id: ChannelId) {
+        if !self.command_channels.contains(&channel_id) {
+            self.command_channels.push(channel_id);
+        }
     }
 
-    pub fn update_domains(&mut self) {
-        if !self.allowed_domains.is_empty() && !self.banned_domains.is_empty() {
-            self.banned_domains.clear();
+    pub fn add_voice_channel(&mut self, channel_id: ChannelId) {
+        if !self.voice_channels.contains(&channel_id) {
+            self.voice_channels.push(channel_id);
         }
+    }
+
+    /// Removes the channel from whichever list it's in, returning whether it was in any.
+    pub fn remove_channel(&mut self, channel_id: ChannelId) -> bool {
+        let count = self.command_channels.len() + self.voice_channels.len();
+        self.command_channels.retain(|id| *id != channel_id);
+        self.voice_channels.retain(|id| *id != channel_id);
+
+        count != self.command_channels.len() + self.voice_channels.len()
+    }
+
+    pub fn clear_channels(&mut self) {
+        self.command_channels.clear();
+        self.voice_channels.clear();
+    }
 
-        if self.allowed_domains.is_empty() && self.banned_domains.is_empty() {
-            self.allowed_domains.insert(String::from("youtube.com"));
-            self.banned_domains.clear();
+    pub fn set_language(&mut self, language: Option<String>) {
+        self.language = language;
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn set_response_public(&mut self, kind: ResponseKind, public: bool) {
+        self.public_responses
+            .retain(|public_kind| *public_kind != kind);
+        if public {
+            self.public_responses.push(kind);
         }
     }
 
-    pub fn set_default_volume(&mut self, volume: f32) {
-        self.default_volume = volume;
+    pub fn is_response_public(&self, kind: ResponseKind) -> bool {
+        self.public_responses.contains(&kind)
     }
+
+    pub fn is_command_channel(&self, channel_id: ChannelId) -> bool {
+        self.command_channels.is_empty() || self.command_channels.contains(&channel_id)
+    }
+
+    pub fn is_voice_channel(&self, channel_id: ChannelId) -> bool {
+        self.voice_channels.is_empty() || self.voice_channels.contains(&channel_id)
+    }
+}
+
+fn default_source_rules() -> Vec<SourceRule> {
+    DEFAULT_ALLOWED_DOMAINS
+        .iter()
+        .filter_map(|domain| SourceRule::allow(domain).ok())
+        .collect()
+}
+
+/// Reduces what was typed as a domain to what's compared against the host of a link,
+/// e.g. `https://YouTube.com/` to `youtube.com`.
+pub fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim();
+    let domain = domain
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(domain);
+    let domain = domain.split('/').next().unwrap_or_default();
+
+    domain.to_lowercase()
 }
 
 pub struct GuildSettingsMap;
@@ -0,0 +1,207 @@
+use std::{
+    env,
+    error::Error,
+    fmt::Display,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use once_cell::sync::OnceCell;
+use rusqlite::{params, Connection, OptionalExtension};
+use serenity::model::id::GuildId;
+
+use crate::{
+    errors::ParrotError,
+    guild::{migrations::migrate, settings::GuildSettings},
+};
+
+const DEFAULT_SETTINGS_PATH: &str = "data/settings";
+const DEFAULT_SETTINGS_DB_PATH: &str = "data/settings.db";
+
+static SETTINGS_STORE: OnceCell<Box<dyn SettingsStore>> = OnceCell::new();
+
+/// Persists the settings of each guild.
+pub trait SettingsStore: Send + Sync {
+    /// The saved settings of the guild, if any were ever saved.
+    fn load(&self, guild_id: GuildId) -> Result<Option<GuildSettings>, ParrotError>;
+
+    fn save(&self, settings: &GuildSettings) -> Result<(), ParrotError>;
+}
+
+#[derive(Debug)]
+pub enum StoreError {
+    /// `SETTINGS_STORE` names a store that doesn't exist.
+    Unknown(String),
+    /// The database couldn't be opened.
+    Open(String, ParrotError),
+}
+
+impl Error for StoreError {}
+
+impl Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(store) => f.write_str(&format!(
+                "Fatality! Unknown SETTINGS_STORE `{store}`, expected `json` or `sqlite`"
+            )),
+            Self::Open(path, err) => f.write_str(&format!(
+                "Fatality! Could not open the settings database at `{path}` ({err}), point to another one with the SETTINGS_DB_PATH variable"
+            )),
+        }
+    }
+}
+
+/// Opens the store picked with `SETTINGS_STORE`, so a misconfigured one is reported on startup
+/// rather than when the first guild's settings are loaded.
+pub fn initialize() -> Result<(), StoreError> {
+    SETTINGS_STORE.get_or_try_init(from_env)?;
+    Ok(())
+}
+
+/// Where guild settings are kept, opened by [`initialize`].
+pub fn settings_store() -> &'static dyn SettingsStore {
+    SETTINGS_STORE
+        .get()
+        .expect("the settings store is opened on startup")
+        .as_ref()
+}
+
+/// Either `json`, the default, which keeps a file per guild in `SETTINGS_PATH`, or `sqlite`,
+/// which keeps every guild in the database at `SETTINGS_DB_PATH`.
+fn from_env() -> Result<Box<dyn SettingsStore>, StoreError> {
+    match env::var("SETTINGS_STORE").as_deref() {
+        Ok("sqlite") => {
+            let path = env::var("SETTINGS_DB_PATH").unwrap_or(DEFAULT_SETTINGS_DB_PATH.to_string());
+            let store = SqliteStore::open(&path).map_err(|err| StoreError::Open(path, err))?;
+            Ok(Box::new(store))
+        }
+        Ok("json") | Err(_) => {
+            let path = env::var("SETTINGS_PATH").unwrap_or(DEFAULT_SETTINGS_PATH.to_string());
+            Ok(Box::new(JsonStore::new(path)))
+        }
+        Ok(other) => Err(StoreError::Unknown(other.to_string())),
+    }
+}
+
+/// A JSON file per guild, named after its id.
+pub struct JsonStore {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl JsonStore {
+    pub fn new(path: impl Into<PathBuf>) -> JsonStore {
+        JsonStore {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    fn guild_path(&self, guild_id: GuildId) -> PathBuf {
+        self.path.join(format!("{}.json", guild_id))
+    }
+}
+
+impl SettingsStore for JsonStore {
+    fn load(&self, guild_id: GuildId) -> Result<Option<GuildSettings>, ParrotError> {
+        let path = self.guild_path(guild_id);
+        if !Path::new(&path).exists() {
+            return Ok(None);
+        }
+
+        let contents = fs::read(path)?;
+        Ok(Some(migrate(serde_json::from_slice(&contents)?)?))
+    }
+
+    /// Writes to a temporary file first and renames it over the old one, syncing both the file
+    /// and its directory, so a crash midway leaves either the old settings or the new ones,
+    /// never half of them.
+    fn save(&self, settings: &GuildSettings) -> Result<(), ParrotError> {
+        let contents = serde_json::to_vec(settings)?;
+
+        let _guard = self.write_lock.lock().unwrap();
+        fs::create_dir_all(&self.path)?;
+
+        let path = self.guild_path(settings.guild_id);
+        let temp_path = path.with_extension("json.tmp");
+
+        let mut file = File::create(&temp_path)?;
+        file.write_all(&contents)?;
+        file.sync_all()?;
+
+        fs::rename(&temp_path, &path)?;
+
+        // directories can only be opened, and so synced, on unix
+        #[cfg(unix)]
+        File::open(&self.path)?.sync_all()?;
+
+        Ok(())
+    }
+}
+
+/// A single SQLite database, with the settings of each guild kept as JSON so new fields
+/// don't need a schema change.
+pub struct SqliteStore {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<SqliteStore, ParrotError> {
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<SqliteStore, ParrotError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<SqliteStore, ParrotError> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS guild_settings (
+                guild_id INTEGER PRIMARY KEY,
+                settings TEXT NOT NULL
+            )",
+        )?;
+
+        Ok(SqliteStore {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+impl SettingsStore for SqliteStore {
+    fn load(&self, guild_id: GuildId) -> Result<Option<GuildSettings>, ParrotError> {
+        let connection = self.connection.lock().unwrap();
+
+        let settings: Option<String> = connection
+            .query_row(
+                "SELECT settings FROM guild_settings WHERE guild_id = ?1",
+                params![guild_id.0 as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match settings {
+            Some(settings) => Ok(Some(migrate(serde_json::from_str(&settings)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn save(&self, settings: &GuildSettings) -> Result<(), ParrotError> {
+        let contents = serde_json::to_string(settings)?;
+        let connection = self.connection.lock().unwrap();
+
+        connection.execute(
+            "INSERT INTO guild_settings (guild_id, settings) VALUES (?1, ?2)
+                ON CONFLICT (guild_id) DO UPDATE SET settings = excluded.settings",
+            params![settings.guild_id.0 as i64, contents],
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+/// Longest template accepted, leaving room in the embed for the rest of the message.
+pub const MAX_TEMPLATE_LEN: usize = 256;
+
+/// How a guild's embeds look.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Theme {
+    /// Color of the strip along the side of embeds, Discord's default when unset.
+    pub accent_color: Option<u32>,
+    /// Shows the artwork of tracks.
+    pub thumbnails: bool,
+    /// Keeps embeds to the track itself, leaving out extra fields and footers.
+    pub compact: bool,
+    /// Replaces the details of the track playing, see [`render_template`].
+    pub now_playing_template: Option<String>,
+    /// Replaces the details of a track added to the queue, see [`render_template`].
+    pub track_queued_template: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            accent_color: None,
+            thumbnails: true,
+            compact: false,
+            now_playing_template: None,
+            track_queued_template: None,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// The theme of the guild whose interaction is being handled.
+    static THEME: Theme;
+}
+
+/// Runs the future with embeds styled after the theme.
+pub async fn with_theme<F: Future>(theme: Theme, future: F) -> F::Output {
+    THEME.scope(theme, future).await
+}
+
+pub fn current_theme() -> Theme {
+    THEME.try_with(Theme::clone).unwrap_or_default()
+}
+
+/// Reads a color written like `#5865F2`, the `#` being optional.
+pub fn parse_color(color: &str) -> Option<u32> {
+    let color = color.trim();
+    let color = color.strip_prefix('#').unwrap_or(color);
+
+    match color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit()) {
+        true => u32::from_str_radix(color, 16).ok(),
+        false => None,
+    }
+}
+
+pub fn format_color(color: u32) -> String {
+    format!("#{:06X}", color)
+}
+
+/// Fills in the `{name}` placeholders of a template, e.g. `{title}` for the track's title.
+/// Placeholders it has no value for are left as they are, and values are never filled in
+/// themselves, so a title containing `{url}` stays that way.
+pub fn render_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let value = rest.find('}').and_then(|end| {
+            let name = &rest[1..end];
+            values
+                .iter()
+                .find(|(placeholder, _)| *placeholder == name)
+                .map(|(_, value)| (end, *value))
+        });
+
+        match value {
+            Some((end, value)) => {
+                text.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    text.push_str(rest);
+    text
+}
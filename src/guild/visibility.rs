@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::messaging::{
+    locale::tr,
+    messages::{RESPONSES_ERRORS, RESPONSES_SETTINGS, RESPONSES_VERSION},
+};
+
+/// Responses that are only shown to whoever ran the command, unless a guild makes them public.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseKind {
+    /// Errors, e.g. not being in a voice channel.
+    Errors,
+    /// The response to `/version`.
+    Version,
+    /// What `/settings` and `/channels` show, which includes e.g. the blocked words.
+    Settings,
+}
+
+impl ResponseKind {
+    pub const ALL: [ResponseKind; 3] = [Self::Errors, Self::Version, Self::Settings];
+
+    /// The name the kind goes by in commands.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Errors => "errors",
+            Self::Version => "version",
+            Self::Settings => "settings",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<ResponseKind> {
+        Self::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Errors => tr(RESPONSES_ERRORS),
+            Self::Version => tr(RESPONSES_VERSION),
+            Self::Settings => tr(RESPONSES_SETTINGS),
+        }
+    }
+}
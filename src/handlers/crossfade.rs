@@ -0,0 +1,160 @@
+use std::{sync::Arc, time::Duration};
+
+use serenity::{
+    async_trait,
+    model::id::GuildId,
+    prelude::{Mutex, RwLock, TypeMap},
+};
+use songbird::{
+    tracks::{PlayMode, TrackHandle},
+    Call, Event, EventContext, EventHandler,
+};
+
+use crate::guild::settings::{GuildSettings, GuildSettingsMap};
+
+/// How long before a track ends the next one is readied, so it starts without a gap.
+const PRELOAD_AHEAD: Duration = Duration::from_secs(10);
+
+/// Polls the playing track to ready the next one ahead of time and, when the guild has a
+/// crossfade set, starts it early while ramping the volume of both tracks.
+pub struct CrossfadeHandler {
+    pub guild_id: GuildId,
+    pub call: Arc<Mutex<Call>>,
+    pub ctx_data: Arc<RwLock<TypeMap>>,
+    pub state: Arc<Mutex<CrossfadeState>>,
+}
+
+#[derive(Default)]
+pub struct CrossfadeState {
+    preloaded: Option<TrackHandle>,
+    fade: Option<Fade>,
+}
+
+struct Fade {
+    outgoing: TrackHandle,
+    incoming: TrackHandle,
+    volume: f32,
+}
+
+#[async_trait]
+impl EventHandler for CrossfadeHandler {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+        let data_rlock = self.ctx_data.read().await;
+        let settings = data_rlock.get::<GuildSettingsMap>().unwrap();
+
+        let crossfade = crossfade_duration(settings.get(&self.guild_id));
+        drop(data_rlock);
+
+        let handler = self.call.lock().await;
+        let queue = handler.queue().current_queue();
+        drop(handler);
+
+        let mut state = self.state.lock().await;
+
+        // the outgoing track ended or was skipped, leave the incoming one at full volume
+        if let Some(fade) = &state.fade {
+            if queue.first().map(|track| track.uuid()) != Some(fade.outgoing.uuid()) {
+                fade.incoming.set_volume(fade.volume).ok();
+                state.fade = None;
+            }
+        }
+
+        let (Some(current), Some(next)) = (queue.first(), queue.get(1)) else {
+            return None;
+        };
+
+        let Ok(info) = current.get_info().await else {
+            return None;
+        };
+
+        // livestreams never end
+        let Some(duration) = current.metadata().duration else {
+            return None;
+        };
+
+        let remaining = duration.saturating_sub(info.position);
+        let playing = matches!(info.playing, PlayMode::Play);
+
+        // pausing or seeking back mid-fade calls it off, the incoming track waits its turn again
+        if let Some(fade) = &state.fade {
+            if is_fade_interrupted(playing, remaining, crossfade)
+                || fade.incoming.uuid() != next.uuid()
+            {
+                fade.outgoing.set_volume(fade.volume).ok();
+                fade.incoming.pause().ok();
+                fade.incoming.seek_time(Duration::ZERO).ok();
+                fade.incoming.set_volume(fade.volume).ok();
+                state.fade = None;
+            }
+        }
+
+        // paused tracks shouldn't fade away
+        if !playing {
+            return None;
+        }
+
+        let preloaded = state.preloaded.as_ref().map(|track| track.uuid());
+        if should_preload(remaining, crossfade) && preloaded != Some(next.uuid()) {
+            next.make_playable().ok();
+            state.preloaded = Some(next.clone());
+        }
+
+        if !is_fading(remaining, crossfade) {
+            return None;
+        }
+
+        let volume = match &state.fade {
+            Some(fade) => fade.volume,
+            None => {
+                next.set_volume(0.0).ok();
+                next.play().ok();
+
+                state.fade = Some(Fade {
+                    outgoing: current.clone(),
+                    incoming: next.clone(),
+                    volume: info.volume,
+                });
+                info.volume
+            }
+        };
+
+        let (outgoing, incoming) = fade_volumes(volume, remaining, crossfade);
+        current.set_volume(outgoing).ok();
+        next.set_volume(incoming).ok();
+
+        None
+    }
+}
+
+/// How long tracks overlap in the guild, autopause stops after every track so there's
+/// nothing to fade into.
+pub fn crossfade_duration(guild_settings: Option<&GuildSettings>) -> Duration {
+    guild_settings
+        .filter(|guild_settings| !guild_settings.autopause)
+        .map(|guild_settings| Duration::from_secs(guild_settings.crossfade))
+        .unwrap_or_default()
+}
+
+/// Whether the next track should be readied, given how much of the current one is left.
+pub fn should_preload(remaining: Duration, crossfade: Duration) -> bool {
+    remaining <= PRELOAD_AHEAD + crossfade
+}
+
+/// Whether the current track is close enough to its end to overlap with the next one.
+pub fn is_fading(remaining: Duration, crossfade: Duration) -> bool {
+    !crossfade.is_zero() && remaining <= crossfade
+}
+
+/// Whether a fade that already started should be called off, because the current track was
+/// paused or sought back to before the crossfade.
+pub fn is_fade_interrupted(playing: bool, remaining: Duration, crossfade: Duration) -> bool {
+    !playing || !is_fading(remaining, crossfade)
+}
+
+/// The volumes of the outgoing and incoming tracks while fading, ramping linearly from the
+/// outgoing track's volume to the incoming one's over the crossfade.
+pub fn fade_volumes(volume: f32, remaining: Duration, crossfade: Duration) -> (f32, f32) {
+    let progress = 1.0 - remaining.as_secs_f32() / crossfade.as_secs_f32();
+    let progress = progress.clamp(0.0, 1.0);
+    (volume * (1.0 - progress), volume * progress)
+}
@@ -4,17 +4,26 @@ use std::sync::{
 };
 
 use serenity::{
-    async_trait, http::Http,
+    async_trait,
+    http::Http,
     model::application::interaction::application_command::ApplicationCommandInteraction,
+    prelude::{RwLock, TypeMap},
 };
 use songbird::{tracks::PlayMode, Event, EventContext, EventHandler, Songbird};
 
-use crate::messaging::messages::IDLE_ALERT;
+use crate::{
+    guild::settings::GuildSettingsMap,
+    messaging::{
+        locale::{resolve_locale, translate},
+        messages::IDLE_ALERT,
+    },
+};
 
 pub struct IdleHandler {
     pub http: Arc<Http>,
     pub manager: Arc<Songbird>,
     pub interaction: ApplicationCommandInteraction,
+    pub ctx_data: Arc<RwLock<TypeMap>>,
     pub limit: usize,
     pub count: Arc<AtomicUsize>,
 }
@@ -42,9 +51,20 @@ impl EventHandler for IdleHandler {
             let guild_id = self.interaction.guild_id?;
 
             if self.manager.remove(guild_id).await.is_ok() {
+                // this runs outside of any interaction, so only the guild's own language applies
+                let data = self.ctx_data.read().await;
+                let alert = data
+                    .get::<GuildSettingsMap>()
+                    .unwrap()
+                    .get(&guild_id)
+                    .and_then(|settings| settings.language.as_deref())
+                    .and_then(resolve_locale)
+                    .map_or(IDLE_ALERT.text, |locale| translate(locale, IDLE_ALERT));
+                drop(data);
+
                 self.interaction
                     .channel_id
-                    .send_message(&self.http, |e| e.embed(|e| e.description(IDLE_ALERT)))
+                    .send_message(&self.http, |e| e.embed(|e| e.description(alert)))
                     .await
                     .unwrap();
             }
@@ -1,7 +1,9 @@
+pub mod crossfade;
 pub mod idle;
 pub mod serenity;
 pub mod track_end;
 
+pub use self::crossfade::CrossfadeHandler;
 pub use self::idle::IdleHandler;
 pub use self::serenity::SerenityHandler;
 pub use self::track_end::TrackEndHandler;
@@ -6,9 +6,12 @@ use serenity::{
         application::interaction::{
             application_command::ApplicationCommandInteraction, Interaction,
         },
+        channel::ChannelType,
         gateway::Ready,
-        id::GuildId,
+        guild::Guild,
+        id::{ChannelId, GuildId},
         prelude::{Activity, VoiceState},
+        Permissions,
     },
     prelude::Mentionable,
 };
@@ -16,11 +19,19 @@ use serenity::{
 use crate::{
     commands::*,
     connection::{check_voice_connections, Connection},
-    errors::ParrotError,
-    guild::settings::{GuildSettings, GuildSettingsMap},
+    errors::{verify, ParrotError},
+    guild::{
+        settings::{GuildSettings, GuildSettingsMap},
+        theme::{with_theme, Theme},
+        visibility::ResponseKind,
+    },
     handlers::track_end::update_queue_messages,
+    messaging::{
+        locale::{resolve_locale, with_locale},
+        messages::FAIL_MANAGE_GUILD,
+    },
     sources::spotify::{Spotify, SPOTIFY},
-    utils::create_response_text,
+    utils::{create_response_ephemeral, is_ephemeral},
 };
 
 pub struct SerenityHandler;
@@ -45,12 +56,24 @@ impl EventHandler for SerenityHandler {
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        let Interaction::ApplicationCommand(mut command) = interaction else {
-            return;
-        };
+        match interaction {
+            Interaction::ApplicationCommand(mut command) => {
+                let locale = self.get_locale(&ctx, &command).await;
+                let theme = self.get_theme(&ctx, &command).await;
 
-        if let Err(err) = self.run_command(&ctx, &mut command).await {
-            self.handle_error(&ctx, &mut command, err).await
+                let run = async {
+                    if let Err(err) = self.run_command(&ctx, &mut command).await {
+                        self.handle_error(&ctx, &mut command, err).await
+                    }
+                };
+                with_theme(theme, with_locale(locale, run)).await
+            }
+            Interaction::Autocomplete(autocomplete_interaction) => {
+                if let Err(err) = autocomplete(&ctx, &autocomplete_interaction).await {
+                    tracing::warn!("failed to suggest choices: {}", err);
+                }
+            }
+            _ => {}
         }
     }
 
@@ -83,12 +106,90 @@ impl SerenityHandler {
                     command
                         .name("autopause")
                         .description("Toggles whether to pause after a song ends")
+                        .default_member_permissions(Permissions::MANAGE_GUILD)
+                })
+                .create_application_command(|command| {
+                    command
+                        .name("channels")
+                        .description("Limits which channels music commands and the bot can be used in")
+                        .default_member_permissions(Permissions::MANAGE_GUILD)
+                        .create_option(|option| {
+                            option
+                                .name("show")
+                                .description("Shows the channels the bot can be used in")
+                                .kind(CommandOptionType::SubCommand)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("add")
+                                .description("Allows a text channel for commands or a voice channel to join")
+                                .kind(CommandOptionType::SubCommand)
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("channel")
+                                        .description("The text or voice channel to allow")
+                                        .kind(CommandOptionType::Channel)
+                                        .channel_types(&[
+                                            ChannelType::Text,
+                                            ChannelType::News,
+                                            ChannelType::Voice,
+                                            ChannelType::Stage,
+                                        ])
+                                        .required(true)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("remove")
+                                .description("Stops allowing a channel")
+                                .kind(CommandOptionType::SubCommand)
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("channel")
+                                        .description("The channel to stop allowing")
+                                        .kind(CommandOptionType::Channel)
+                                        .required(true)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("clear")
+                                .description("Allows every channel again")
+                                .kind(CommandOptionType::SubCommand)
+                        })
                 })
                 .create_application_command(|command| {
                     command
                         .name("clear")
                         .description("Clears the queue")
                 })
+                .create_application_command(|command| {
+                    command
+                        .name("crossfade")
+                        .description("Overlaps the end of a track with the start of the next one")
+                        .default_member_permissions(Permissions::MANAGE_GUILD)
+                        .create_option(|option| {
+                            option
+                                .name("seconds")
+                                .description("How long the tracks overlap, 0 disables crossfading")
+                                .kind(CommandOptionType::Integer)
+                                .min_int_value(0)
+                                .max_int_value(MAX_CROSSFADE_SECS)
+                                .required(true)
+                        })
+                })
+                .create_application_command(|command| {
+                    command
+                        .name("dedupe")
+                        .description("Removes tracks that are queued more than once")
+                        .create_option(|option| {
+                            option
+                                .name("prevent")
+                                .description("Whether to also reject duplicates queued from now on")
+                                .kind(CommandOptionType::Boolean)
+                                .required(false)
+                        })
+                })
                 .create_application_command(|command| {
                     command
                         .name("eidolon")
@@ -108,15 +209,55 @@ impl SerenityHandler {
                                     .required(false)
                         })
                 })
+                .create_application_command(|command| {
+                    command
+                        .name("forward")
+                        .description("Fast-forwards the current track, to the next chapter if it has any")
+                        .create_option(|option| {
+                            option
+                                .name("seconds")
+                                .description("How many seconds to fast-forward")
+                                .kind(CommandOptionType::Integer)
+                                .min_int_value(1)
+                                .required(false)
+                        })
+                })
                 .create_application_command(|command| {
                     command
                         .name("leave")
                         .description("Leave the voice channel the bot is connected to")
                 })
+                .create_application_command(|command| {
+                    command
+                        .name("lyrics")
+                        .description("Shows the lyrics of the current track")
+                })
                 .create_application_command(|command| {
                     command
                         .name("managesources")
                         .description("Manage streaming from different sources")
+                        .default_member_permissions(Permissions::MANAGE_GUILD)
+                })
+                .create_application_command(|command| {
+                    command
+                        .name("move")
+                        .description("Moves a track to another position in the queue")
+                        .create_option(|option| {
+                            option
+                                .name("from")
+                                .description("Track to move, by position or title")
+                                .kind(CommandOptionType::String)
+                                .required(true)
+                                .set_autocomplete(true)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("to")
+                                .description("Position to move the track to (1 is the next track to be played)")
+                                .kind(CommandOptionType::Integer)
+                                .required(true)
+                                .min_int_value(1)
+                        })
                 })
                 .create_application_command(|command| {
                     command
@@ -212,27 +353,82 @@ impl SerenityHandler {
                 .create_application_command(|command| {
                     command
                         .name("queue")
-                        .description("Shows the queue")
+                        .description("Shows, exports or imports the queue")
+                        .create_option(|option| {
+                            option
+                                .name("show")
+                                .description("Shows the queue")
+                                .kind(CommandOptionType::SubCommand)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("export")
+                                .description("Saves the queue to a file")
+                                .kind(CommandOptionType::SubCommand)
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("format")
+                                        .description("The format of the file, JSON by default")
+                                        .kind(CommandOptionType::String)
+                                        .add_string_choice("JSON", "json")
+                                        .add_string_choice("M3U", "m3u")
+                                        .required(false)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("import")
+                                .description("Adds the tracks of an exported queue")
+                                .kind(CommandOptionType::SubCommand)
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("file")
+                                        .description("A queue saved with /queue export")
+                                        .kind(CommandOptionType::Attachment)
+                                        .required(true)
+                                })
+                        })
                 })
                 .create_application_command(|command| {
                     command
                         .name("remove")
-                        .description("Removes a track from the queue")
+                        .description("Removes tracks from the queue")
                         .create_option(|option| {
                             option
                                 .name("index")
-                                .description("Position of the track in the queue (1 is the next track to be played)")
-                                .kind(CommandOptionType::Integer)
-                                .required(true)
-                                .min_int_value(1)
+                                .description("Track to remove, by position (1 is the next track to be played) or title")
+                                .kind(CommandOptionType::String)
+                                .required(false)
+                                .set_autocomplete(true)
                         })
                         .create_option(|option| {
                             option
                                 .name("until")
-                                .description("Upper range track position to remove a range of tracks")
-                                .kind(CommandOptionType::Integer)
+                                .description("Last track of a range of tracks to remove")
+                                .kind(CommandOptionType::String)
+                                .required(false)
+                                .set_autocomplete(true)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("user")
+                                .description("Removes every track this user requested")
+                                .kind(CommandOptionType::User)
+                                .required(false)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("mine")
+                                .description("Removes every track you requested")
+                                .kind(CommandOptionType::Boolean)
+                                .required(false)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("match")
+                                .description("Removes every track with this text in its title")
+                                .kind(CommandOptionType::String)
                                 .required(false)
-                                .min_int_value(1)
                         })
                 })
                 .create_application_command(|command| {
@@ -240,6 +436,19 @@ impl SerenityHandler {
                         .name("repeat")
                         .description("Toggles looping for the current track")
                 })
+                .create_application_command(|command| {
+                    command
+                        .name("rewind")
+                        .description("Rewinds the current track, to the start of the chapter if it has any")
+                        .create_option(|option| {
+                            option
+                                .name("seconds")
+                                .description("How many seconds to rewind")
+                                .kind(CommandOptionType::Integer)
+                                .min_int_value(1)
+                                .required(false)
+                        })
+                })
                 .create_application_command(|command| {
                     command
                         .name("roll")
@@ -259,11 +468,71 @@ impl SerenityHandler {
                         .create_option(|option| {
                             option
                                 .name("timestamp")
-                                .description("Timestamp in the format HH:MM:SS")
+                                .description("Timestamp in the format HH:MM:SS, or +SS and -SS to move relative to the current position")
                                 .kind(CommandOptionType::String)
                                 .required(true)
                         })
                 })
+                .create_application_command(|command| {
+                    command
+                        .name("settings")
+                        .description("Shows, changes, exports or imports the settings of this server")
+                        .default_member_permissions(Permissions::MANAGE_GUILD)
+                        .create_option(|option| {
+                            option
+                                .name("show")
+                                .description("Shows the settings and lets you change them")
+                                .kind(CommandOptionType::SubCommand)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("export")
+                                .description("Saves the settings to a file")
+                                .kind(CommandOptionType::SubCommand)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("import")
+                                .description("Applies settings saved with /settings export")
+                                .kind(CommandOptionType::SubCommand)
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("file")
+                                        .description("A file made by /settings export")
+                                        .kind(CommandOptionType::Attachment)
+                                        .required(true)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("reset")
+                                .description("Resets the settings to their defaults")
+                                .kind(CommandOptionType::SubCommand)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("responses")
+                                .description("Chooses whether everyone sees a kind of response")
+                                .kind(CommandOptionType::SubCommand)
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("kind")
+                                        .description("The kind of response")
+                                        .kind(CommandOptionType::String)
+                                        .add_string_choice("Errors", "errors")
+                                        .add_string_choice("/version", "version")
+                                        .add_string_choice("/settings and /channels", "settings")
+                                        .required(true)
+                                })
+                                .create_sub_option(|option| {
+                                    option
+                                        .name("public")
+                                        .description("Whether everyone sees them")
+                                        .kind(CommandOptionType::Boolean)
+                                        .required(true)
+                                })
+                        })
+                })
                 .create_application_command(|command| {
                     command.name("shuffle").description("Shuffles the queue")
                 })
@@ -272,10 +541,10 @@ impl SerenityHandler {
                     .create_option(|option| {
                         option
                             .name("to")
-                            .description("Track index to skip to")
-                            .kind(CommandOptionType::Integer)
+                            .description("Track to skip to, by position or title")
+                            .kind(CommandOptionType::String)
                             .required(false)
-                            .min_int_value(1)
+                            .set_autocomplete(true)
                     })
                 })
                 .create_application_command(|command| {
@@ -361,19 +630,37 @@ impl SerenityHandler {
             "Running user issued command",
         );
 
+        // importing a queue joins the user's channel, just like playing a track does
+        let subcommand_name = command
+            .data
+            .options
+            .first()
+            .map(|option| option.name.as_str());
+        let is_queue_import = command_name == "queue" && subcommand_name == Some("import");
+
+        // music commands are kept to the channels the server allows, managing the bot is not
+        if !matches!(
+            command_name,
+            "channels" | "eidolon" | "managesources" | "roll" | "settings" | "version"
+        ) {
+            self.check_channels(ctx, command, &guild, is_queue_import)
+                .await?;
+        }
+
         match command_name {
-            "autopause" | "clear" | "leave" | "pause" | "remove" | "repeat" | "resume" | "seek"
-            | "shuffle" | "skip" | "stop" | "volume" | "voteskip" => {
-                match check_voice_connections(&guild, &user_id, &bot_id) {
-                    Connection::User(_) | Connection::Neither => Err(ParrotError::NotConnected),
-                    Connection::Bot(bot_channel_id) => {
-                        Err(ParrotError::AuthorDisconnected(bot_channel_id.mention()))
-                    }
-                    Connection::Separate(_, _) => Err(ParrotError::WrongVoiceChannel),
-                    _ => Ok(()),
+            "autopause" | "clear" | "dedupe" | "forward" | "leave" | "move" | "pause"
+            | "remove" | "repeat" | "resume" | "rewind" | "seek" | "shuffle" | "skip" | "stop"
+            | "volume" | "voteskip" => match check_voice_connections(&guild, &user_id, &bot_id) {
+                Connection::User(_) | Connection::Neither => Err(ParrotError::NotConnected),
+                Connection::Bot(bot_channel_id) => {
+                    Err(ParrotError::AuthorDisconnected(bot_channel_id.mention()))
                 }
-            }
-            "play" | "superplay" | "summon" => {
+                Connection::Separate(_, _) => Err(ParrotError::WrongVoiceChannel),
+                _ => Ok(()),
+            },
+            "play" | "superplay" | "summon" | "queue"
+                if command_name != "queue" || is_queue_import =>
+            {
                 match check_voice_connections(&guild, &user_id, &bot_id) {
                     Connection::User(_) => Ok(()),
                     Connection::Bot(_) if command_name == "summon" => {
@@ -396,12 +683,43 @@ impl SerenityHandler {
             _ => Ok(()),
         }?;
 
+        // changing settings takes Manage Server, and since the permission set on the command
+        // can be overridden per server it's checked here too, /dedupe only needs it to prevent
+        // duplicates from now on
+        let changes_settings = match command_name {
+            "autopause" | "channels" | "crossfade" | "managesources" | "settings" => true,
+            "dedupe" => command
+                .data
+                .options
+                .iter()
+                .any(|option| option.name == "prevent"),
+            _ => false,
+        };
+
+        if changes_settings {
+            let permissions = command
+                .member
+                .as_ref()
+                .and_then(|member| member.permissions)
+                .unwrap_or_default();
+            verify(
+                permissions.manage_guild(),
+                ParrotError::Message(FAIL_MANAGE_GUILD),
+            )?;
+        }
+
         match command_name {
             "autopause" => autopause(ctx, command).await,
+            "channels" => channels(ctx, command).await,
             "clear" => clear(ctx, command).await,
+            "crossfade" => crossfade(ctx, command).await,
+            "dedupe" => dedupe(ctx, command).await,
             "eidolon" => eidolon(ctx, command).await,
+            "forward" => forward(ctx, command).await,
             "leave" => leave(ctx, command).await,
+            "lyrics" => lyrics(ctx, command).await,
             "managesources" => allow(ctx, command).await,
+            "move" => move_track(ctx, command).await,
             "np" => now_playing(ctx, command).await,
             "pause" => pause(ctx, command).await,
             "play" | "superplay" => play(ctx, command).await,
@@ -409,8 +727,10 @@ impl SerenityHandler {
             "remove" => remove(ctx, command).await,
             "repeat" => repeat(ctx, command).await,
             "resume" => resume(ctx, command).await,
+            "rewind" => rewind(ctx, command).await,
             "roll" => roll(ctx, command).await,
             "seek" => seek(ctx, command).await,
+            "settings" => settings(ctx, command).await,
             "shuffle" => shuffle(ctx, command).await,
             "skip" => skip(ctx, command).await,
             "stop" => stop(ctx, command).await,
@@ -422,6 +742,72 @@ impl SerenityHandler {
         }
     }
 
+    /// Keeps commands to the allowed text channels and the bot out of voice channels it may
+    /// not join, each allowing any channel until one is added.
+    async fn check_channels(
+        &self,
+        ctx: &Context,
+        command: &ApplicationCommandInteraction,
+        guild: &Guild,
+        is_queue_import: bool,
+    ) -> Result<(), ParrotError> {
+        let data = ctx.data.read().await;
+        let Some(settings) = data
+            .get::<GuildSettingsMap>()
+            .and_then(|settings| settings.get(&guild.id))
+        else {
+            return Ok(());
+        };
+
+        let mentions =
+            |channels: &[ChannelId]| channels.iter().map(|id| id.mention()).collect::<Vec<_>>();
+
+        if !settings.is_command_channel(command.channel_id) {
+            return Err(ParrotError::CommandChannel(mentions(
+                &settings.command_channels,
+            )));
+        }
+
+        let command_name = command.data.name.as_str();
+        let joins = matches!(command_name, "play" | "superplay" | "summon") || is_queue_import;
+        let bot_id = ctx.cache.current_user_id();
+
+        match check_voice_connections(guild, &command.user.id, &bot_id) {
+            Connection::User(channel_id) if joins && !settings.is_voice_channel(channel_id) => Err(
+                ParrotError::VoiceChannel(mentions(&settings.voice_channels)),
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// The server's language when it picked one, otherwise the language of whoever ran the
+    /// command, if there is a translation for either.
+    async fn get_locale(
+        &self,
+        ctx: &Context,
+        command: &ApplicationCommandInteraction,
+    ) -> Option<&'static str> {
+        let data = ctx.data.read().await;
+        let language = command.guild_id.and_then(|guild_id| {
+            data.get::<GuildSettingsMap>()?
+                .get(&guild_id)?
+                .language
+                .as_deref()
+                .and_then(resolve_locale)
+        });
+
+        language.or_else(|| resolve_locale(&command.locale))
+    }
+
+    async fn get_theme(&self, ctx: &Context, command: &ApplicationCommandInteraction) -> Theme {
+        let data = ctx.data.read().await;
+        command
+            .guild_id
+            .and_then(|guild_id| data.get::<GuildSettingsMap>()?.get(&guild_id))
+            .map(|settings| settings.theme.clone())
+            .unwrap_or_default()
+    }
+
     async fn self_deafen(&self, ctx: &Context, guild: Option<GuildId>, new: VoiceState) {
         let Ok(user) = ctx.http.get_current_user().await else {
             return;
@@ -444,7 +830,13 @@ impl SerenityHandler {
         interaction: &mut ApplicationCommandInteraction,
         err: ParrotError,
     ) {
-        create_response_text(&ctx.http, interaction, &format!("{err}"))
+        // where to use the bot only concerns whoever ran the command, even when errors are public
+        let ephemeral = match err {
+            ParrotError::CommandChannel(_) | ParrotError::VoiceChannel(_) => true,
+            _ => is_ephemeral(ctx, interaction.guild_id, ResponseKind::Errors).await,
+        };
+
+        create_response_ephemeral(&ctx.http, interaction, &format!("{err}"), ephemeral)
             .await
             .expect("failed to create response");
     }
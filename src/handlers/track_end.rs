@@ -13,7 +13,9 @@ use crate::{
         queue::{build_nav_btns, calculate_num_pages, create_queue_embed, forget_queue_message},
         voteskip::forget_skip_votes,
     },
-    guild::{cache::GuildCacheMap, settings::GuildSettingsMap},
+    guild::{cache::GuildCacheMap, settings::GuildSettingsMap, theme::with_theme},
+    messaging::locale::{resolve_locale, with_locale},
+    sources::audio_cache::prefetch_queue,
 };
 
 pub struct TrackEndHandler {
@@ -40,12 +42,16 @@ impl EventHandler for TrackEndHandler {
             .map(|guild_settings| guild_settings.autopause)
             .unwrap_or_default();
 
+        let handler = self.call.lock().await;
+        let queue = handler.queue();
+
         if autopause {
-            let handler = self.call.lock().await;
-            let queue = handler.queue();
             queue.pause().ok();
         }
 
+        prefetch_queue(&queue.current_queue());
+        drop(handler);
+
         drop(data_rlock);
         forget_skip_votes(&self.ctx_data, self.guild_id).await.ok();
 
@@ -78,6 +84,15 @@ pub async fn update_queue_messages(
         Some(cache) => cache.queue_messages.clone(),
         None => return,
     };
+
+    // there's no interaction to take the language from, so only the guild's own applies
+    let guild_settings = data.get::<GuildSettingsMap>().unwrap().get(&guild_id);
+    let locale = guild_settings
+        .and_then(|settings| settings.language.as_deref())
+        .and_then(resolve_locale);
+    let theme = guild_settings
+        .map(|settings| settings.theme.clone())
+        .unwrap_or_default();
     drop(data);
 
     for (message, page_lock) in messages.iter_mut() {
@@ -86,7 +101,11 @@ pub async fn update_queue_messages(
         let mut page = page_lock.write().await;
         *page = usize::min(*page, num_pages - 1);
 
-        let embed = create_queue_embed(tracks, *page);
+        let embed = with_theme(
+            theme.clone(),
+            with_locale(locale, async { create_queue_embed(tracks, *page) }),
+        )
+        .await;
 
         let edit_message = message
             .edit(&http, |edit| {
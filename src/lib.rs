@@ -1,6 +1,7 @@
 pub mod commands;
 pub mod guild;
 pub mod handlers;
+pub mod lyrics;
 pub mod messaging;
 pub mod metrics;
 pub mod sources;
@@ -9,6 +10,7 @@ pub mod sources;
 pub mod test;
 
 pub mod client;
+pub mod config;
 pub mod connection;
 pub mod errors;
 pub mod utils;
@@ -0,0 +1,149 @@
+use std::process::Stdio;
+
+use serde_json::Value;
+use serenity::async_trait;
+use tokio::process::Command;
+
+use crate::{
+    config::YTDL,
+    errors::ParrotError,
+    lyrics::{LyricsProvider, LyricsQuery},
+    messaging::messages::LYRICS_FETCH_FAILED,
+    sources::HTTP_CLIENT,
+};
+
+const CAPTIONS_LANGUAGE: &str = "en";
+const CAPTIONS_FORMAT: &str = "vtt";
+
+/// Falls back to the captions of the video itself, as transcribed by YouTube when the
+/// uploader provided none. These are rough, but better than nothing for covers and live
+/// performances that no lyrics site knows about.
+#[derive(Clone, Copy)]
+pub struct YouTubeCaptions;
+
+#[async_trait]
+impl LyricsProvider for YouTubeCaptions {
+    fn name(&self) -> &'static str {
+        "YouTube captions"
+    }
+
+    async fn lyrics(&self, query: &LyricsQuery) -> Result<Option<String>, ParrotError> {
+        let Some(url) = &query.source_url else {
+            return Ok(None);
+        };
+
+        let ytdl_args = [
+            "-j",              // print JSON information, which lists the caption tracks
+            "--skip-download", // only the captions are needed
+            "--no-playlist",   // only look at the video if URL also has playlist info
+            "--ignore-config", // disable all configuration files for a yt-dlp run
+            "--no-warnings",   // don't print out warnings
+            url,
+        ];
+
+        let output = Command::new(&YTDL.path)
+            .args(ytdl_args)
+            .args(&YTDL.args)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|_| ParrotError::Message(LYRICS_FETCH_FAILED))?;
+
+        let info: Value = serde_json::from_slice(&output.stdout)
+            .map_err(|_| ParrotError::Message(LYRICS_FETCH_FAILED))?;
+
+        // captions from the uploader are more accurate than automatic ones
+        let Some(captions_url) =
+            captions_url(&info["subtitles"]).or_else(|| captions_url(&info["automatic_captions"]))
+        else {
+            return Ok(None);
+        };
+
+        let captions = HTTP_CLIENT
+            .get(captions_url)
+            .send()
+            .await
+            .map_err(|_| ParrotError::Message(LYRICS_FETCH_FAILED))?
+            .text()
+            .await
+            .map_err(|_| ParrotError::Message(LYRICS_FETCH_FAILED))?;
+
+        Ok(Some(parse_vtt(&captions)).filter(|text| !text.is_empty()))
+    }
+}
+
+/// Picks the English track, or one of its regional variants, in the WebVTT format.
+fn captions_url(tracks: &Value) -> Option<String> {
+    let tracks = tracks.as_object()?;
+
+    let formats = tracks.get(CAPTIONS_LANGUAGE).or_else(|| {
+        tracks
+            .iter()
+            .find(|(lang, _)| lang.starts_with(&format!("{}-", CAPTIONS_LANGUAGE)))
+            .map(|(_, formats)| formats)
+    })?;
+
+    formats
+        .as_array()?
+        .iter()
+        .find(|format| format["ext"] == CAPTIONS_FORMAT)
+        .and_then(|format| format["url"].as_str())
+        .map(String::from)
+}
+
+/// Extracts the text of a WebVTT file, dropping cue timings and styling. Automatic captions
+/// repeat each line as the next one scrolls in, so consecutive duplicates are dropped too.
+pub fn parse_vtt(vtt: &str) -> String {
+    let mut lines: Vec<String> = vec![];
+    // the header, comments and style blocks all run until the next blank line
+    let mut in_block = true;
+
+    for line in vtt.lines() {
+        let line = line.trim();
+
+        if in_block || line.starts_with("NOTE") || line.starts_with("STYLE") {
+            in_block = !line.is_empty();
+            continue;
+        }
+
+        if line.is_empty() || line.contains("-->") {
+            continue;
+        }
+
+        // cues may be numbered
+        if line.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        let text = strip_tags(line)
+            .replace("&amp;", "&")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&nbsp;", " ");
+        let text = text.trim();
+
+        if text.is_empty() || lines.last().map(String::as_str) == Some(text) {
+            continue;
+        }
+
+        lines.push(text.to_string());
+    }
+
+    lines.join("\n")
+}
+
+fn strip_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut in_tag = false;
+
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
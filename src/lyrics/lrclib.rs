@@ -0,0 +1,80 @@
+use std::env;
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use serenity::async_trait;
+
+use crate::{
+    errors::ParrotError,
+    lyrics::{LyricsProvider, LyricsQuery},
+    messaging::messages::LYRICS_FETCH_FAILED,
+    sources::HTTP_CLIENT,
+};
+
+const DEFAULT_LRCLIB_API_URL: &str = "https://lrclib.net";
+
+/// Results whose length is further off than this are likely a different recording.
+const MAX_DURATION_DRIFT: f64 = 5.0;
+
+lazy_static! {
+    pub static ref LRCLIB: Lrclib =
+        Lrclib::new(env::var("LRCLIB_API_URL").unwrap_or(DEFAULT_LRCLIB_API_URL.to_string()));
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LrclibTrack {
+    duration: Option<f64>,
+    #[serde(default)]
+    instrumental: bool,
+    plain_lyrics: Option<String>,
+}
+
+/// Looks lyrics up on [LRCLIB](https://lrclib.net), which needs no API key.
+#[derive(Clone)]
+pub struct Lrclib {
+    api_url: String,
+}
+
+impl Lrclib {
+    pub fn new(api_url: String) -> Lrclib {
+        Lrclib { api_url }
+    }
+}
+
+#[async_trait]
+impl LyricsProvider for Lrclib {
+    fn name(&self) -> &'static str {
+        "LRCLIB"
+    }
+
+    async fn lyrics(&self, query: &LyricsQuery) -> Result<Option<String>, ParrotError> {
+        let mut params = vec![("track_name", query.title.as_str())];
+        if let Some(artist) = &query.artist {
+            params.push(("artist_name", artist.as_str()));
+        }
+
+        let tracks: Vec<LrclibTrack> = HTTP_CLIENT
+            .get(format!("{}/api/search", self.api_url))
+            .query(&params)
+            .send()
+            .await
+            .map_err(|_| ParrotError::Message(LYRICS_FETCH_FAILED))?
+            .json()
+            .await
+            .map_err(|_| ParrotError::Message(LYRICS_FETCH_FAILED))?;
+
+        let duration = query.duration.map(|duration| duration.as_secs_f64());
+
+        let lyrics = tracks
+            .into_iter()
+            .filter(|track| !track.instrumental)
+            .filter(|track| match (duration, track.duration) {
+                (Some(expected), Some(actual)) => (expected - actual).abs() <= MAX_DURATION_DRIFT,
+                _ => true,
+            })
+            .find_map(|track| track.plain_lyrics);
+
+        Ok(lyrics)
+    }
+}
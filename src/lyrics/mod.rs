@@ -0,0 +1,127 @@
+pub mod captions;
+pub mod lrclib;
+
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serenity::async_trait;
+use songbird::input::Metadata;
+
+use crate::{
+    errors::ParrotError,
+    lyrics::{captions::YouTubeCaptions, lrclib::LRCLIB},
+};
+
+lazy_static! {
+    // suffixes like "(Official Video)" or "[Lyrics]" that would throw off a lookup by title
+    static ref TITLE_NOISE_REGEX: Regex = Regex::new(
+        r"(?i)\s*[(\[][^)\]]*\b(official|video|audio|lyrics?|visuali[sz]er|hd|4k|remaster(ed)?|mv)\b[^)\]]*[)\]]"
+    )
+    .unwrap();
+}
+
+/// What is known about a track when looking up its lyrics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LyricsQuery {
+    pub title: String,
+    pub artist: Option<String>,
+    pub duration: Option<Duration>,
+    pub source_url: Option<String>,
+}
+
+impl LyricsQuery {
+    /// Builds a query from the track metadata. Videos rarely fill in the artist, so it's taken
+    /// from an `Artist - Title` title or the uploading channel instead.
+    pub fn from_metadata(metadata: &Metadata) -> Option<LyricsQuery> {
+        let title = metadata.track.as_ref().or(metadata.title.as_ref())?;
+        let title = TITLE_NOISE_REGEX.replace_all(title, "").trim().to_string();
+
+        let (artist, title) = match (&metadata.artist, title.split_once(" - ")) {
+            (Some(artist), _) => (Some(artist.clone()), title),
+            (None, Some((artist, title))) => {
+                (Some(artist.trim().to_string()), title.trim().to_string())
+            }
+            (None, None) => (
+                metadata
+                    .channel
+                    .as_ref()
+                    .map(|channel| channel.trim_end_matches(" - Topic").to_string()),
+                title,
+            ),
+        };
+
+        Some(LyricsQuery {
+            title,
+            artist,
+            duration: metadata.duration,
+            source_url: metadata.source_url.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lyrics {
+    pub text: String,
+    pub provider: &'static str,
+}
+
+#[async_trait]
+pub trait LyricsProvider: Send + Sync {
+    /// Name shown next to the lyrics to credit where they came from.
+    fn name(&self) -> &'static str;
+
+    async fn lyrics(&self, query: &LyricsQuery) -> Result<Option<String>, ParrotError>;
+}
+
+/// The providers `/lyrics` goes through, in order of preference.
+pub fn default_providers() -> Vec<Box<dyn LyricsProvider>> {
+    vec![Box::new(LRCLIB.clone()), Box::new(YouTubeCaptions)]
+}
+
+/// Asks each provider in turn, returning the first lyrics found. A failing provider is
+/// skipped so the next one still gets a chance.
+pub async fn find_lyrics(
+    providers: &[Box<dyn LyricsProvider>],
+    query: &LyricsQuery,
+) -> Option<Lyrics> {
+    for provider in providers {
+        match provider.lyrics(query).await {
+            Ok(Some(text)) if !text.trim().is_empty() => {
+                return Some(Lyrics {
+                    text,
+                    provider: provider.name(),
+                })
+            }
+            Ok(_) => {}
+            Err(err) => tracing::warn!("{} lyrics lookup failed: {}", provider.name(), err),
+        }
+    }
+
+    None
+}
+
+/// Splits the lyrics into pages that fit an embed, breaking between lines.
+pub fn paginate_lyrics(text: &str, page_size: usize) -> Vec<String> {
+    let mut pages = vec![];
+    let mut page = String::new();
+
+    for line in text.trim().lines() {
+        let line: String = line.chars().take(page_size).collect();
+
+        if !page.is_empty() && page.chars().count() + line.chars().count() + 1 > page_size {
+            pages.push(std::mem::take(&mut page));
+        }
+
+        if !page.is_empty() {
+            page.push('\n');
+        }
+        page.push_str(&line);
+    }
+
+    if !page.trim().is_empty() || pages.is_empty() {
+        pages.push(page);
+    }
+
+    pages
+}
@@ -0,0 +1,82 @@
+use std::{collections::HashMap, future::Future};
+
+use lazy_static::lazy_static;
+
+/// Translations shipped with the bot, by the locale Discord reports for its users.
+/// A translation starts out as a copy of `locales/template.json` and is listed here,
+/// messages it leaves empty are shown in English.
+const TRANSLATIONS: &[(&str, &str)] = &[("en-US", include_str!("../../locales/en-US.json"))];
+
+/// A message shown to users, known to translations by its name since different messages
+/// can share the same English text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub name: &'static str,
+    pub text: &'static str,
+}
+
+lazy_static! {
+    static ref LOCALES: HashMap<&'static str, HashMap<String, String>> = TRANSLATIONS
+        .iter()
+        .filter_map(|(locale, contents)| match parse_catalog(contents) {
+            Ok(messages) => Some((*locale, messages)),
+            Err(err) => {
+                tracing::error!(locale = ?locale, err = ?err, "Failed to read translation");
+                None
+            }
+        })
+        .collect();
+}
+
+tokio::task_local! {
+    /// The locale responses are written in while handling an interaction.
+    static LOCALE: Option<&'static str>;
+}
+
+/// Runs the future with its messages translated to the locale, if there is one.
+pub async fn with_locale<F: Future>(locale: Option<&'static str>, future: F) -> F::Output {
+    LOCALE.scope(locale, future).await
+}
+
+/// Translates a message to the locale of the interaction being handled, messages that
+/// have no translation are in English.
+pub fn tr(message: Message) -> &'static str {
+    match LOCALE.try_with(|locale| *locale) {
+        Ok(Some(locale)) => translate(locale, message),
+        _ => message.text,
+    }
+}
+
+pub fn translate(locale: &str, message: Message) -> &'static str {
+    LOCALES
+        .get(locale)
+        .and_then(|messages| messages.get(message.name))
+        .filter(|translation| !translation.is_empty())
+        .map(String::as_str)
+        .unwrap_or(message.text)
+}
+
+/// Finds the translation for a locale, settling for another variant of the same
+/// language, e.g. `en-US` for `en-GB`.
+pub fn resolve_locale(locale: &str) -> Option<&'static str> {
+    let language = |locale: &str| locale.split('-').next().unwrap_or_default().to_lowercase();
+
+    available_locales()
+        .into_iter()
+        .find(|available| available.eq_ignore_ascii_case(locale))
+        .or_else(|| {
+            available_locales()
+                .into_iter()
+                .find(|available| language(available) == language(locale))
+        })
+}
+
+pub fn available_locales() -> Vec<&'static str> {
+    let mut locales: Vec<_> = LOCALES.keys().copied().collect();
+    locales.sort_unstable();
+    locales
+}
+
+pub fn parse_catalog(contents: &str) -> Result<HashMap<String, String>, serde_json::Error> {
+    serde_json::from_str(contents)
+}
@@ -2,7 +2,10 @@ use std::fmt::Display;
 
 use serenity::model::mention::Mention;
 
-use crate::messaging::messages::*;
+use crate::{
+    guild::visibility::ResponseKind,
+    messaging::{locale::tr, messages::*},
+};
 
 const RELEASES_LINK: &str = "https://github.com/aquelemiguel/parrot/releases";
 
@@ -10,66 +13,202 @@ const RELEASES_LINK: &str = "https://github.com/aquelemiguel/parrot/releases";
 pub enum ParrotMusicMessage {
     AutopauseOff,
     AutopauseOn,
+    ChannelAddedCommands {
+        mention: Mention,
+    },
+    ChannelAddedVoice {
+        mention: Mention,
+    },
+    ChannelRemoved {
+        mention: Mention,
+    },
+    ChannelsCleared,
     Clear,
+    CrossfadeOff,
+    CrossfadeOn {
+        seconds: u64,
+    },
+    Dedupe {
+        removed: usize,
+        no_duplicates: Option<bool>,
+    },
     Error,
     Leaving,
     LoopDisable,
     LoopEnable,
+    LyricsSearch,
+    Move {
+        title: String,
+        url: String,
+        position: usize,
+    },
     NowPlaying,
     Pause,
     PlayAllFailed,
-    PlayDomainBanned { domain: String },
-    PlaylistQueued,
+    PlayDomainBanned {
+        domain: String,
+    },
+    PlaylistQueued {
+        skipped: usize,
+    },
+    QueueImported {
+        imported: usize,
+        skipped: usize,
+    },
     RemoveMultiple,
+    Responses {
+        kind: ResponseKind,
+        public: bool,
+    },
     Resume,
     Search,
-    Seek { timestamp: String },
+    Seek {
+        timestamp: String,
+    },
+    SeekChapter {
+        title: String,
+    },
+    SettingsReset,
     Shuffle,
     Skip,
     SkipAll,
-    SkipTo { title: String, url: String },
+    SkipTo {
+        title: String,
+        url: String,
+    },
     Stop,
-    Summon { mention: Mention },
-    Version { current: String },
-    VoteSkip { mention: Mention, missing: usize },
+    Summon {
+        mention: Mention,
+    },
+    Version {
+        current: String,
+    },
+    VoteSkip {
+        mention: Mention,
+        missing: usize,
+    },
 }
 
 impl Display for ParrotMusicMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::AutopauseOff => f.write_str(AUTOPAUSE_OFF),
-            Self::AutopauseOn => f.write_str(AUTOPAUSE_ON),
-            Self::Clear => f.write_str(CLEARED),
-            Self::Error => f.write_str(ERROR),
-            Self::Leaving => f.write_str(LEAVING),
-            Self::LoopDisable => f.write_str(LOOP_DISABLED),
-            Self::LoopEnable => f.write_str(LOOP_ENABLED),
-            Self::NowPlaying => f.write_str(QUEUE_NOW_PLAYING),
-            Self::Pause => f.write_str(PAUSED),
-            Self::PlaylistQueued => f.write_str(PLAY_PLAYLIST),
-            Self::PlayAllFailed => f.write_str(PLAY_ALL_FAILED),
-            Self::PlayDomainBanned { domain } => {
-                f.write_str(&format!("⚠️ **{}** {}", domain, PLAY_FAILED_BLOCKED_DOMAIN))
+            Self::AutopauseOff => f.write_str(tr(AUTOPAUSE_OFF)),
+            Self::AutopauseOn => f.write_str(tr(AUTOPAUSE_ON)),
+            Self::ChannelAddedCommands { mention } => {
+                f.write_str(&format!("{} {}!", tr(CHANNELS_ADDED_COMMANDS), mention))
             }
-            Self::Search => f.write_str(SEARCHING),
-            Self::RemoveMultiple => f.write_str(REMOVED_QUEUE_MULTIPLE),
-            Self::Resume => f.write_str(RESUMED),
-            Self::Shuffle => f.write_str(SHUFFLED_SUCCESS),
-            Self::Stop => f.write_str(STOPPED),
+            Self::ChannelAddedVoice { mention } => {
+                f.write_str(&format!("{} {}!", tr(CHANNELS_ADDED_VOICE), mention))
+            }
+            Self::ChannelRemoved { mention } => f.write_str(&format!(
+                "{} {} {}",
+                tr(CHANNELS_REMOVED),
+                mention,
+                tr(CHANNELS_REMOVED_FROM)
+            )),
+            Self::ChannelsCleared => f.write_str(tr(CHANNELS_CLEARED)),
+            Self::Clear => f.write_str(tr(CLEARED)),
+            Self::CrossfadeOff => f.write_str(tr(CROSSFADE_OFF)),
+            Self::CrossfadeOn { seconds } => {
+                f.write_str(&format!("{} **{}s**!", tr(CROSSFADE_ON), seconds))
+            }
+            Self::Dedupe {
+                removed,
+                no_duplicates,
+            } => {
+                match removed {
+                    0 => f.write_str(tr(DEDUPED_NONE))?,
+                    _ => f.write_str(&format!(
+                        "{} **{}** {}",
+                        tr(DEDUPED),
+                        removed,
+                        tr(DEDUPED_TRACKS)
+                    ))?,
+                };
+                match no_duplicates {
+                    Some(true) => f.write_str(&format!("\n{}", tr(NO_DUPLICATES_ON))),
+                    Some(false) => f.write_str(&format!("\n{}", tr(NO_DUPLICATES_OFF))),
+                    None => Ok(()),
+                }
+            }
+            Self::Error => f.write_str(tr(ERROR)),
+            Self::Leaving => f.write_str(tr(LEAVING)),
+            Self::LoopDisable => f.write_str(tr(LOOP_DISABLED)),
+            Self::LoopEnable => f.write_str(tr(LOOP_ENABLED)),
+            Self::LyricsSearch => f.write_str(tr(LYRICS_SEARCHING)),
+            Self::Move {
+                title,
+                url,
+                position,
+            } => f.write_str(&format!(
+                "{} [**{}**]({}) {} **{}**!",
+                tr(MOVED),
+                title,
+                url,
+                tr(MOVED_TO),
+                position
+            )),
+            Self::NowPlaying => f.write_str(tr(QUEUE_NOW_PLAYING)),
+            Self::Pause => f.write_str(tr(PAUSED)),
+            Self::PlaylistQueued { skipped } => {
+                f.write_str(tr(PLAY_PLAYLIST))?;
+                match skipped {
+                    0 => Ok(()),
+                    _ => f.write_str(&format!("\n**{}** {}", skipped, tr(PLAY_PLAYLIST_SKIPPED))),
+                }
+            }
+            Self::PlayAllFailed => f.write_str(tr(PLAY_ALL_FAILED)),
+            Self::PlayDomainBanned { domain } => f.write_str(&format!(
+                "⚠️ **{}** {}",
+                domain,
+                tr(PLAY_FAILED_BLOCKED_DOMAIN)
+            )),
+            Self::QueueImported { imported, skipped } => {
+                f.write_str(&format!("{} **{}** tracks!", tr(QUEUE_IMPORTED), imported))?;
+                match skipped {
+                    0 => Ok(()),
+                    _ => f.write_str(&format!("\n**{}** {}", skipped, tr(QUEUE_IMPORT_SKIPPED))),
+                }
+            }
+            Self::Search => f.write_str(tr(SEARCHING)),
+            Self::RemoveMultiple => f.write_str(tr(REMOVED_QUEUE_MULTIPLE)),
+            Self::Responses { kind, public } => {
+                let visibility = match public {
+                    true => tr(RESPONSES_PUBLIC),
+                    false => tr(RESPONSES_PRIVATE),
+                };
+                f.write_str(&format!("{} {}!", visibility, kind.description()))
+            }
+            Self::Resume => f.write_str(tr(RESUMED)),
+            Self::Shuffle => f.write_str(tr(SHUFFLED_SUCCESS)),
+            Self::Stop => f.write_str(tr(STOPPED)),
             Self::VoteSkip { mention, missing } => f.write_str(&format!(
                 "{}{} {} {} {}",
-                SKIP_VOTE_EMOJI, mention, SKIP_VOTE_USER, missing, SKIP_VOTE_MISSING
+                tr(SKIP_VOTE_EMOJI),
+                mention,
+                tr(SKIP_VOTE_USER),
+                missing,
+                tr(SKIP_VOTE_MISSING)
             )),
-            Self::Seek { timestamp } => f.write_str(&format!("{} **{}**!", SEEKED, timestamp)),
-            Self::Skip => f.write_str(SKIPPED),
-            Self::SkipAll => f.write_str(SKIPPED_ALL),
+            Self::Seek { timestamp } => f.write_str(&format!("{} **{}**!", tr(SEEKED), timestamp)),
+            Self::SeekChapter { title } => {
+                f.write_str(&format!("{} **{}**!", tr(SEEKED_CHAPTER), title))
+            }
+            Self::SettingsReset => f.write_str(tr(SETTINGS_RESET)),
+            Self::Skip => f.write_str(tr(SKIPPED)),
+            Self::SkipAll => f.write_str(tr(SKIPPED_ALL)),
             Self::SkipTo { title, url } => {
-                f.write_str(&format!("{} [**{}**]({})!", SKIPPED_TO, title, url))
+                f.write_str(&format!("{} [**{}**]({})!", tr(SKIPPED_TO), title, url))
             }
-            Self::Summon { mention } => f.write_str(&format!("{} **{}**!", JOINING, mention)),
+            Self::Summon { mention } => f.write_str(&format!("{} **{}**!", tr(JOINING), mention)),
             Self::Version { current } => f.write_str(&format!(
                 "{} [{}]({}/tag/v{})\n{}({}/latest)",
-                VERSION, current, RELEASES_LINK, current, VERSION_LATEST, RELEASES_LINK
+                tr(VERSION),
+                current,
+                RELEASES_LINK,
+                current,
+                tr(VERSION_LATEST),
+                RELEASES_LINK
             )),
         }
     }
@@ -1,68 +1,190 @@
-pub const AUTOPAUSE_OFF: &str = "🤖 Autopause OFF!";
-pub const AUTOPAUSE_ON: &str = "🤖 Autopause ON!";
-pub const CLEARED: &str = "🗑️ Cleared!";
+use crate::messaging::locale::Message;
 
-pub const DOMAIN_FORM_ALLOWED_TITLE: &str = "Allowed domains";
-pub const DOMAIN_FORM_BANNED_TITLE: &str = "Banned domains";
-pub const DOMAIN_FORM_ALLOWED_PLACEHOLDER: &str = "Add domains separated by \';\'. If left blank, all (except for banned) are allowed by default.";
-pub const DOMAIN_FORM_BANNED_PLACEHOLDER: &str = "Add domains separated by \';\'. If left blank, all (except for allowed) are blocked by default.";
-pub const DOMAIN_FORM_TITLE: &str = "Manage sources";
+/// Declares the messages along with [`CATALOG`]. They're written like string constants,
+/// but each becomes a [`Message`] named after its constant, which translations are keyed on.
+macro_rules! messages {
+    ($(pub const $name:ident: &str = $text:literal;)*) => {
+        $(pub const $name: Message = Message { name: stringify!($name), text: $text };)*
 
-pub const ERROR: &str = "Fatality! Something went wrong ☹️";
-pub const FAIL_ALREADY_HERE: &str = "⚠️ I'm already here!";
-pub const FAIL_ANOTHER_CHANNEL: &str = "⚠️ I'm already connected to";
-pub const FAIL_AUTHOR_DISCONNECTED: &str = "⚠️ You are not connected to";
-pub const FAIL_AUTHOR_NOT_FOUND: &str = "⚠️ Could not find you in any voice channel!";
-pub const FAIL_LOOP: &str = "⚠️ Failed to toggle loop!";
-pub const FAIL_MINUTES_PARSING: &str = "⚠️ Invalid formatting for 'minutes'";
-pub const FAIL_NO_SONG_ON_INDEX: &str = "⚠️ There is no queued song on that index!";
-pub const FAIL_NO_VOICE_CONNECTION: &str = "⚠️ I'm not connected to any voice channel!";
-pub const FAIL_REMOVE_RANGE: &str = "⚠️ `until` needs to be higher than `index`!";
-pub const FAIL_SECONDS_PARSING: &str = "⚠️ Invalid formatting for 'seconds'";
-pub const FAIL_WRONG_CHANNEL: &str = "⚠️ We are not in the same voice channel!";
-pub const FAIL_VOLUME_PARSING: &str = "⚠️ Invalid formatting for 'volume'";
-pub const IDLE_ALERT: &str = "I've been idle for a while, so I'll leave for now to save resources.\nFeel free to summon me back any time!";
-pub const JOINING: &str = "Joining";
-pub const LEAVING: &str = "👋 See you soon!";
-pub const LOOP_DISABLED: &str = "🔁 Disabled loop!";
-pub const LOOP_ENABLED: &str = "🔁 Enabled loop!";
-pub const NOTHING_IS_PLAYING: &str = "🔈 Nothing is playing!";
-pub const PAUSED: &str = "⏸️ Paused!";
-pub const PLAY_FAILED_BLOCKED_DOMAIN: &str = "**is either not allowed in this server or is not supported!** \n\nTo explicitely allow this domain, ask a moderator to run the `/managesources` command. [Click to see a list of supported sources.](https://github.com/yt-dlp/yt-dlp/blob/master/supportedsites.md)";
-pub const PLAY_ALL_FAILED: &str = "⚠️ Cannot fetch playlist via keywords! Try passing this command an URL.";
-pub const PLAY_PLAYLIST: &str = "📃 Added playlist to queue!";
-pub const PLAY_QUEUE: &str = "📃 Added to queue!";
-pub const PLAY_TOP: &str = "📃 Added to top!";
-pub const QUEUE_EXPIRED: &str = "In order to save resources, this command has expired.\nPlease feel free to reinvoke it!";
-pub const QUEUE_IS_EMPTY: &str = "Queue is empty!";
-pub const QUEUE_NO_SONGS: &str = "There's no songs up next!";
-pub const QUEUE_NOTHING_IS_PLAYING: &str = "Nothing is playing!";
-pub const QUEUE_NOW_PLAYING: &str = "🔊 Now playing";
-pub const QUEUE_PAGE_OF: &str = "of";
-pub const QUEUE_PAGE: &str = "Page";
-pub const QUEUE_UP_NEXT: &str = "⌛ Up next";
-pub const REMOVED_QUEUE_MULTIPLE: &str = "❌ Removed multiple tracks from queue!";
-pub const REMOVED_QUEUE: &str = "❌ Removed from queue";
-pub const RESUMED: &str = "▶️ Resumed!";
-pub const SEARCHING: &str = "🔎 Searching...";
-pub const SEEKED: &str = "⏩ Seeked current track to";
-pub const SHUFFLED_SUCCESS: &str = "🔀 Shuffled successfully!";
-pub const SKIP_VOTE_EMOJI: &str = "🗳 ";
-pub const SKIP_VOTE_MISSING: &str = "more vote(s) needed to skip!";
-pub const SKIP_VOTE_USER: &str = "has voted to skip!";
-pub const SKIPPED_ALL: &str = "⏭️ Skipped until infinity!";
-pub const SKIPPED_TO: &str = "⏭️ Skipped to";
-pub const SKIPPED: &str = "⏭️ Skipped!";
-pub const SPOTIFY_AUTH_FAILED: &str = "⚠️ **Could not authenticate with Spotify!**\nDid you forget to provide your Spotify application's client ID and secret?";
-pub const SPOTIFY_INVALID_QUERY: &str = "⚠️ **Could not find any tracks with that link!**\nAre you sure that is a valid Spotify URL?";
-pub const SPOTIFY_PLAYLIST_FAILED: &str = "⚠️ **Failed to fetch playlist!**\nIt's likely that this playlist is either private or a personalized recommendation playlist generated by Spotify.";
-pub const STOPPED: &str = "⏹️ Stopped!";
-pub const TRACK_DURATION: &str = "Track duration: ";
-pub const TRACK_NOT_FOUND: &str = "⚠️ **Could not play track!**\nYour request yielded no results.";
-pub const TRACK_INAPPROPRIATE: &str = "⚠️ **Could not play track!**\nThe video you requested may be inappropriate for some users, so sign-in is required.";
-pub const TRACK_TIME_TO_PLAY: &str = "Estimated time until play: ";
-pub const VERSION_LATEST: &str = "Find the latest version [here]";
-pub const VERSION: &str = "Version";
+        /// Every message, which is what translations are expected to cover.
+        pub const CATALOG: &[Message] = &[$($name),*];
+    };
+}
+
+messages! {
+    pub const APPLE_MUSIC_AUTH_FAILED: &str = "⚠️ **Could not fetch Apple Music playlist!**\nPlaylists can only be fetched if an Apple Music developer token is provided.";
+    pub const APPLE_MUSIC_INVALID_QUERY: &str = "⚠️ **Could not find any tracks with that link!**\nAre you sure that is a valid Apple Music URL?";
+    pub const APPLE_MUSIC_PLAYLIST_FAILED: &str = "⚠️ **Failed to fetch playlist!**\nIt's likely that this playlist is either private or not available in this region.";
+    pub const AUTOPAUSE_OFF: &str = "🤖 Autopause OFF!";
+    pub const AUTOPAUSE_ON: &str = "🤖 Autopause ON!";
+    pub const CROSSFADE_OFF: &str = "🎚️ Crossfade OFF!";
+    pub const CROSSFADE_ON: &str = "🎚️ Crossfading tracks over";
+    pub const CHANNELS_ADDED_COMMANDS: &str = "📌 Music commands can now be used in";
+    pub const CHANNELS_ADDED_VOICE: &str = "📌 I can now join";
+    pub const CHANNELS_CLEARED: &str = "📌 Music commands can be used anywhere and I'll join any voice channel again!";
+    pub const CHANNELS_COMMANDS: &str = "Command channels";
+    pub const CHANNELS_INVALID_KIND: &str = "⚠️ Only text and voice channels can be allowed!";
+    pub const CHANNELS_NOT_LISTED: &str = "⚠️ That channel isn't one of the allowed channels!";
+    pub const CHANNELS_REMOVED: &str = "📌 Removed";
+    pub const CHANNELS_REMOVED_FROM: &str = "from the allowed channels!";
+    pub const CHANNELS_TITLE: &str = "📌 Channels";
+    pub const CHANNELS_VOICE: &str = "Voice channels";
+    pub const CLEARED: &str = "🗑️ Cleared!";
+
+    pub const DEDUPED: &str = "🧹 Removed";
+    pub const DEDUPED_NONE: &str = "🧹 There are no duplicates in the queue!";
+    pub const DEDUPED_TRACKS: &str = "duplicate tracks from the queue!";
+    pub const DEEZER_INVALID_QUERY: &str = "⚠️ **Could not find any tracks with that link!**\nAre you sure that is a valid Deezer URL?";
+    pub const DEEZER_PLAYLIST_FAILED: &str = "⚠️ **Failed to fetch playlist!**\nIt's likely that this playlist is either private or no longer exists.";
+    pub const DOMAIN_FORM_INVALID_RULES: &str = "⚠️ **Some rules could not be read, so nothing was changed!**\nRules start with `allow` or `ban`, followed by a domain like `*.bandcamp.com`, a link like `youtube.com/playlist?list=*` or a `/regex/`.";
+    pub const DOMAIN_FORM_RULES_PLACEHOLDER: &str = "One rule per line, e.g. allow youtube.com, ban *.bandcamp.com or allow /regex/";
+    pub const DOMAIN_FORM_RULES_TITLE: &str = "Rules, the first one that matches decides";
+    pub const DOMAIN_FORM_TITLE: &str = "Manage sources";
+
+    pub const ERROR: &str = "Fatality! Something went wrong ☹️";
+    pub const FAIL_ALREADY_HERE: &str = "⚠️ I'm already here!";
+    pub const FAIL_ANOTHER_CHANNEL: &str = "⚠️ I'm already connected to";
+    pub const FAIL_AUTHOR_DISCONNECTED: &str = "⚠️ You are not connected to";
+    pub const FAIL_AUTHOR_NOT_FOUND: &str = "⚠️ Could not find you in any voice channel!";
+    pub const FAIL_COMMAND_CHANNEL: &str = "⚠️ Music commands can only be used in";
+    pub const FAIL_LOOP: &str = "⚠️ Failed to toggle loop!";
+    pub const FAIL_MANAGE_GUILD: &str = "⚠️ You need the **Manage Server** permission to change the settings!";
+    pub const FAIL_NO_SONG_ON_INDEX: &str = "⚠️ There is no queued song on that index!";
+    pub const FAIL_NO_VOICE_CONNECTION: &str = "⚠️ I'm not connected to any voice channel!";
+    pub const FAIL_REMOVE_RANGE: &str = "⚠️ `until` needs to be higher than `index`!";
+    pub const FAIL_TIMESTAMP_PARSING: &str = "⚠️ Invalid timestamp, try `1:30`, `+30` or `-1:00`!";
+    pub const FAIL_WRONG_CHANNEL: &str = "⚠️ We are not in the same voice channel!";
+    pub const FAIL_VOICE_CHANNEL: &str = "⚠️ I can only join";
+    pub const FAIL_VOLUME_PARSING: &str = "⚠️ Invalid formatting for 'volume'";
+    pub const IDLE_ALERT: &str = "I've been idle for a while, so I'll leave for now to save resources.\nFeel free to summon me back any time!";
+    pub const JOINING: &str = "Joining";
+    pub const LANGUAGE_NAME: &str = "English";
+    pub const LEAVING: &str = "👋 See you soon!";
+    pub const LOOP_DISABLED: &str = "🔁 Disabled loop!";
+    pub const LOOP_ENABLED: &str = "🔁 Enabled loop!";
+    pub const LYRICS_FETCH_FAILED: &str = "⚠️ Failed to fetch lyrics!";
+    pub const LYRICS_NOT_FOUND: &str = "⚠️ Could not find lyrics for this track!";
+    pub const LYRICS_PROVIDED_BY: &str = "Lyrics from";
+    pub const LYRICS_SEARCHING: &str = "🔎 Looking for lyrics...";
+    pub const MOVED: &str = "↕️ Moved";
+    pub const MOVED_TO: &str = "to position";
+    pub const NO_DUPLICATES_OFF: &str = "✅ Tracks can be queued more than once again!";
+    pub const NO_DUPLICATES_ON: &str = "🚫 Tracks already in the queue will be rejected from now on!";
+    pub const NOTHING_IS_PLAYING: &str = "🔈 Nothing is playing!";
+    pub const PAUSED: &str = "⏸️ Paused!";
+    pub const PLAY_FAILED_BLOCKED_DOMAIN: &str = "**is either not allowed in this server or is not supported!** \n\nTo explicitely allow this domain, ask a moderator to run the `/managesources` command. [Click to see a list of supported sources.](https://github.com/yt-dlp/yt-dlp/blob/master/supportedsites.md)";
+    pub const PLAY_ALL_FAILED: &str = "⚠️ Cannot fetch playlist via keywords! Try passing this command an URL.";
+    pub const PLAYLIST_FAILED: &str = "⚠️ **Failed to fetch playlist!**\nIt's likely that this playlist is either private or no longer exists.";
+    pub const PLAYLIST_INTERRUPTED: &str = "⚠️ **Stopped fetching playlist!**\nSomething went wrong while reading its tracks, so it may have been added partially.";
+    pub const PLAY_PLAYLIST: &str = "📃 Added playlist to queue!";
+    pub const PLAY_PLAYLIST_ALL_SKIPPED: &str = "⚠️ None of the tracks could be queued!\nThey're either already in the queue, filtered out or couldn't be found.";
+    pub const PLAY_PLAYLIST_SKIPPED: &str = "tracks were left out, they're either already in the queue, filtered out or couldn't be found.";
+    pub const PLAY_QUEUE: &str = "📃 Added to queue!";
+    pub const PLAY_TOP: &str = "📃 Added to top!";
+    pub const QUEUE_EXPIRED: &str = "In order to save resources, this command has expired.\nPlease feel free to reinvoke it!";
+    pub const QUEUE_EXPORTED: &str = "📤 Exported";
+    pub const QUEUE_IMPORTED: &str = "📥 Imported";
+    pub const QUEUE_IMPORT_INVALID: &str = "⚠️ **Could not read that file!**\nQueues can be imported from the `.json` and `.m3u` files made by `/queue export`.";
+    pub const QUEUE_IMPORT_SKIPPED: &str = "tracks could not be queued, come from sources that aren't allowed or went past the limit of a single import.";
+    pub const QUEUE_IMPORT_TOO_LARGE: &str = "⚠️ That file is too large to be a queue!";
+    pub const QUEUE_IS_EMPTY: &str = "Queue is empty!";
+    pub const QUEUE_NO_SONGS: &str = "There's no songs up next!";
+    pub const QUEUE_NOTHING_IS_PLAYING: &str = "Nothing is playing!";
+    pub const QUEUE_NOW_PLAYING: &str = "🔊 Now playing";
+    pub const QUEUE_PAGE_OF: &str = "of";
+    pub const QUEUE_PAGE: &str = "Page";
+    pub const QUEUE_UP_NEXT: &str = "⌛ Up next";
+    pub const REMOVE_CANCEL: &str = "Cancel";
+    pub const REMOVE_CANCELLED: &str = "↩️ Kept the tracks in the queue!";
+    pub const REMOVE_CONFIRM: &str = "Remove";
+    pub const REMOVE_CONFIRM_PROMPT: &str = "⚠️ This will remove";
+    pub const REMOVE_CONFIRM_TRACKS: &str = "tracks from the queue, are you sure?";
+    pub const REMOVE_NO_FILTER: &str = "⚠️ Pick the tracks to remove with `index`, `user`, `mine` or `match`!";
+    pub const REMOVE_NOTHING_MATCHED: &str = "⚠️ No queued tracks match that!";
+    pub const REMOVED_QUEUE_MULTIPLE: &str = "❌ Removed multiple tracks from queue!";
+    pub const REMOVED_QUEUE: &str = "❌ Removed from queue";
+    pub const RESPONSES_ERRORS: &str = "errors";
+    pub const RESPONSES_PRIVATE: &str = "🔒 From now on, only whoever ran the command sees";
+    pub const RESPONSES_PUBLIC: &str = "📢 From now on, everyone sees";
+    pub const RESPONSES_SETTINGS: &str = "`/settings` and `/channels` responses";
+    pub const RESPONSES_VERSION: &str = "`/version` responses";
+    pub const RESUMED: &str = "▶️ Resumed!";
+    pub const SEARCHING: &str = "🔎 Searching...";
+    pub const SEEKED: &str = "⏩ Seeked current track to";
+    pub const SEEKED_CHAPTER: &str = "⏩ Seeked current track to chapter";
+    pub const SEEK_CHAPTER_PLACEHOLDER: &str = "Seek to chapter";
+    pub const SETTINGS_ACCENT_COLOR: &str = "Accent color";
+    pub const SETTINGS_ACCENT_COLOR_PLACEHOLDER: &str = "e.g. #5865F2, leave blank for the default";
+    pub const SETTINGS_AGE_RESTRICTED: &str = "Age-restricted videos";
+    pub const SETTINGS_AGE_RESTRICTED_REJECTED: &str = "Reject age-restricted";
+    pub const SETTINGS_ALLOWED: &str = "Allowed";
+    pub const SETTINGS_ANY: &str = "Any";
+    pub const SETTINGS_AUTOPAUSE: &str = "Autopause";
+    pub const SETTINGS_BLOCKED_WORDS: &str = "Blocked words";
+    pub const SETTINGS_CATEGORIES: &str = "Allowed categories";
+    pub const SETTINGS_COMPACT: &str = "Compact";
+    pub const SETTINGS_CROSSFADE: &str = "Crossfade";
+    pub const SETTINGS_CROSSFADE_PLACEHOLDER: &str = "Change the crossfade";
+    pub const SETTINGS_DEFAULT: &str = "Default";
+    pub const SETTINGS_DUPLICATES: &str = "Duplicates";
+    pub const SETTINGS_DUPLICATES_REJECTED: &str = "Reject duplicates";
+    pub const SETTINGS_EXPORTED: &str = "📤 Exported the settings of this server!";
+    pub const SETTINGS_FILTER_CATEGORIES_PLACEHOLDER: &str = "e.g. Music, leave blank to allow any category";
+    pub const SETTINGS_FILTER_TITLE: &str = "Content filter";
+    pub const SETTINGS_FILTER_WORDS_PLACEHOLDER: &str = "Words or phrases, one per line or separated by commas";
+    pub const SETTINGS_IMPORTED: &str = "📥 Imported the settings!";
+    pub const SETTINGS_IMPORT_CANCEL: &str = "Cancel";
+    pub const SETTINGS_IMPORT_CANCELLED: &str = "↩️ Kept the current settings!";
+    pub const SETTINGS_IMPORT_CONFIRM: &str = "Import";
+    pub const SETTINGS_IMPORT_INVALID: &str = "⚠️ **Could not read that file!**\nSettings can be imported from the `.json` files made by `/settings export`.";
+    pub const SETTINGS_IMPORT_PROMPT: &str = "📥 Importing these settings will change the following, are you sure?";
+    pub const SETTINGS_IMPORT_TOO_LARGE: &str = "⚠️ That file is too large to be settings!";
+    pub const SETTINGS_IMPORT_UNCHANGED: &str = "📥 These settings are the same as the current ones!";
+    pub const SETTINGS_LANGUAGE: &str = "Language";
+    pub const SETTINGS_LANGUAGE_USER: &str = "Each member's own";
+    pub const SETTINGS_NONE: &str = "None";
+    pub const SETTINGS_NOW_PLAYING_TEMPLATE: &str = "Now playing template";
+    pub const SETTINGS_NOW_PLAYING_TEMPLATE_PLACEHOLDER: &str = "e.g. 🎶 [{title}]({url}) by {channel}, {position} / {duration}";
+    pub const SETTINGS_OFF: &str = "Off";
+    pub const SETTINGS_ON: &str = "On";
+    pub const SETTINGS_PUBLIC_RESPONSES: &str = "Public responses";
+    pub const SETTINGS_REJECTED: &str = "Rejected";
+    pub const SETTINGS_RESPONSES_INVALID: &str = "⚠️ Pick which responses to show and whether everyone sees them!";
+    pub const SETTINGS_RESET: &str = "🔄 Reset the settings of this server to their defaults!";
+    pub const SETTINGS_SOURCES: &str = "Sources";
+    pub const SETTINGS_THEME: &str = "Theme";
+    pub const SETTINGS_THEME_INVALID: &str = "⚠️ **Could not change the theme!**\nColors are written like `#5865F2` and templates can be up to 256 characters long.";
+    pub const SETTINGS_THUMBNAILS: &str = "Thumbnails";
+    pub const SETTINGS_TITLE: &str = "⚙️ Settings";
+    pub const SETTINGS_TRACK_QUEUED_TEMPLATE: &str = "Queued track template";
+    pub const SETTINGS_TRACK_QUEUED_TEMPLATE_PLACEHOLDER: &str = "e.g. [{title}]({url}) plays in {eta}";
+    pub const SETTINGS_VOLUME: &str = "Default volume";
+    pub const SETTINGS_VOLUME_PLACEHOLDER: &str = "Change the default volume";
+    pub const SHUFFLED_SUCCESS: &str = "🔀 Shuffled successfully!";
+    pub const SKIP_VOTE_EMOJI: &str = "🗳 ";
+    pub const SKIP_VOTE_MISSING: &str = "more vote(s) needed to skip!";
+    pub const SKIP_VOTE_USER: &str = "has voted to skip!";
+    pub const SKIPPED_ALL: &str = "⏭️ Skipped until infinity!";
+    pub const SKIPPED_TO: &str = "⏭️ Skipped to";
+    pub const SKIPPED: &str = "⏭️ Skipped!";
+    pub const SPOTIFY_AUTH_FAILED: &str = "⚠️ **Could not authenticate with Spotify!**\nDid you forget to provide your Spotify application's client ID and secret?";
+    pub const SPOTIFY_INVALID_QUERY: &str = "⚠️ **Could not find any tracks with that link!**\nAre you sure that is a valid Spotify URL?";
+    pub const SPOTIFY_PLAYLIST_FAILED: &str = "⚠️ **Failed to fetch playlist!**\nIt's likely that this playlist is either private or a personalized recommendation playlist generated by Spotify.";
+    pub const STOPPED: &str = "⏹️ Stopped!";
+    pub const TIDAL_AUTH_FAILED: &str = "⚠️ **Could not authenticate with Tidal!**\nDid you forget to provide a Tidal API token?";
+    pub const TIDAL_INVALID_QUERY: &str = "⚠️ **Could not find any tracks with that link!**\nAre you sure that is a valid Tidal URL?";
+    pub const TRACK_DUPLICATE: &str = "⚠️ **Could not play track!**\nIt's already in the queue.";
+    pub const TRACK_DURATION: &str = "Track duration: ";
+    pub const TRACK_FILTERED_AGE_RESTRICTED: &str = "⚠️ **Could not play track!**\nThis server doesn't allow age-restricted videos.";
+    pub const TRACK_FILTERED_BLOCKED_WORD: &str = "⚠️ **Could not play track!**\nIts title contains words this server has blocked.";
+    pub const TRACK_FILTERED_CATEGORY: &str = "⚠️ **Could not play track!**\nIt isn't in any of the categories this server allows.";
+    pub const TRACK_NOT_FOUND: &str = "⚠️ **Could not play track!**\nYour request yielded no results.";
+    pub const TRACK_INAPPROPRIATE: &str = "⚠️ **Could not play track!**\nThe video you requested may be inappropriate for some users, so sign-in is required.";
+    pub const TRACK_TIME_TO_PLAY: &str = "Estimated time until play: ";
+    pub const YTDL_MISSING: &str = "⚠️ **Could not fetch media!**\nyt-dlp is not installed or could not be started on the host.";
+    pub const VERSION_LATEST: &str = "Find the latest version [here]";
+    pub const VERSION: &str = "Version";
+}
 
 pub const CHANGE_DAY: [&str; 3] = [
     "You've all done a terrific job! Well... All but one of you. You know who you are. Please report to HR.",
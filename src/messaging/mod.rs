@@ -1,3 +1,4 @@
+pub mod locale;
 pub mod message;
 #[rustfmt::skip]
 pub mod messages;
@@ -0,0 +1,243 @@
+use std::{env, str::FromStr};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    commands::play::QueryType,
+    errors::ParrotError,
+    messaging::messages::{
+        APPLE_MUSIC_AUTH_FAILED, APPLE_MUSIC_INVALID_QUERY, APPLE_MUSIC_PLAYLIST_FAILED,
+    },
+    sources::HTTP_CLIENT,
+};
+
+const DEFAULT_ITUNES_API_URL: &str = "https://itunes.apple.com";
+const DEFAULT_APPLE_MUSIC_API_URL: &str = "https://api.music.apple.com";
+
+lazy_static! {
+    pub static ref APPLE_MUSIC: AppleMusic = AppleMusic::new(
+        env::var("ITUNES_API_URL").unwrap_or(DEFAULT_ITUNES_API_URL.to_string()),
+        env::var("APPLE_MUSIC_API_URL").unwrap_or(DEFAULT_APPLE_MUSIC_API_URL.to_string()),
+        env::var("APPLE_MUSIC_TOKEN").ok(),
+    );
+    pub static ref APPLE_MUSIC_QUERY_REGEX: Regex = Regex::new(
+        r"music.apple.com/(?P<storefront>[a-z]{2})/(?P<media_type>[a-z]+)/(?:[^/?]+/)?(?P<media_id>[^/?]+)"
+    )
+    .unwrap();
+}
+
+#[derive(Clone, Copy)]
+pub enum MediaType {
+    Song,
+    Album,
+    Playlist,
+}
+
+impl FromStr for MediaType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "song" => Ok(Self::Song),
+            "album" => Ok(Self::Album),
+            "playlist" => Ok(Self::Playlist),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    results: Vec<LookupResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LookupResult {
+    wrapper_type: String,
+    artist_name: Option<String>,
+    track_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CatalogResponse {
+    data: Vec<CatalogPlaylist>,
+}
+
+#[derive(Deserialize)]
+struct CatalogPlaylist {
+    relationships: CatalogRelationships,
+}
+
+#[derive(Deserialize)]
+struct CatalogRelationships {
+    tracks: CatalogTracks,
+}
+
+#[derive(Deserialize)]
+struct CatalogTracks {
+    data: Vec<CatalogTrack>,
+}
+
+#[derive(Deserialize)]
+struct CatalogTrack {
+    attributes: CatalogTrackAttributes,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogTrackAttributes {
+    name: String,
+    artist_name: String,
+}
+
+/// Songs and albums are resolved through the public iTunes lookup API, while playlists
+/// are only available through the Apple Music API, which requires a developer token.
+pub struct AppleMusic {
+    lookup_url: String,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl AppleMusic {
+    pub fn new(lookup_url: String, api_url: String, token: Option<String>) -> AppleMusic {
+        AppleMusic {
+            lookup_url,
+            api_url,
+            token,
+        }
+    }
+
+    pub async fn extract(&self, query: &str) -> Result<QueryType, ParrotError> {
+        let captures = APPLE_MUSIC_QUERY_REGEX
+            .captures(query)
+            .ok_or(ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?;
+
+        let storefront = captures
+            .name("storefront")
+            .ok_or(ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?
+            .as_str();
+
+        let media_type = captures
+            .name("media_type")
+            .ok_or(ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?
+            .as_str();
+
+        let media_type = MediaType::from_str(media_type)
+            .map_err(|_| ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?;
+
+        let media_id = captures
+            .name("media_id")
+            .ok_or(ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?
+            .as_str();
+
+        // a song shared from within an album points to the album and selects it with `?i=`
+        let song_id = Url::parse(query).ok().and_then(|url| {
+            url.query_pairs()
+                .find(|(key, _)| key == "i")
+                .map(|(_, value)| value.to_string())
+        });
+
+        match (media_type, song_id) {
+            (MediaType::Album, Some(song_id)) => self.get_song_info(storefront, &song_id).await,
+            (MediaType::Song, _) => self.get_song_info(storefront, media_id).await,
+            (MediaType::Album, None) => self.get_album_info(storefront, media_id).await,
+            (MediaType::Playlist, _) => self.get_playlist_info(storefront, media_id).await,
+        }
+    }
+
+    async fn get_song_info(&self, storefront: &str, id: &str) -> Result<QueryType, ParrotError> {
+        let query_list = self.lookup(storefront, id, false).await?;
+
+        let query = query_list
+            .into_iter()
+            .next()
+            .ok_or(ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?;
+
+        Ok(QueryType::Keywords(query))
+    }
+
+    async fn get_album_info(&self, storefront: &str, id: &str) -> Result<QueryType, ParrotError> {
+        let query_list = self.lookup(storefront, id, true).await?;
+        Ok(QueryType::KeywordList(query_list))
+    }
+
+    async fn get_playlist_info(
+        &self,
+        storefront: &str,
+        id: &str,
+    ) -> Result<QueryType, ParrotError> {
+        let token = self
+            .token
+            .as_ref()
+            .ok_or(ParrotError::Message(APPLE_MUSIC_AUTH_FAILED))?;
+
+        let response: CatalogResponse = HTTP_CLIENT
+            .get(format!(
+                "{}/v1/catalog/{}/playlists/{}",
+                self.api_url, storefront, id
+            ))
+            .bearer_auth(token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|_| ParrotError::Message(APPLE_MUSIC_PLAYLIST_FAILED))?
+            .json()
+            .await
+            .map_err(|_| ParrotError::Message(APPLE_MUSIC_PLAYLIST_FAILED))?;
+
+        let query_list: Vec<String> = response
+            .data
+            .iter()
+            .flat_map(|playlist| playlist.relationships.tracks.data.iter())
+            .map(|track| Self::build_query(&track.attributes.artist_name, &track.attributes.name))
+            .collect();
+
+        Ok(QueryType::KeywordList(query_list))
+    }
+
+    async fn lookup(
+        &self,
+        storefront: &str,
+        id: &str,
+        with_songs: bool,
+    ) -> Result<Vec<String>, ParrotError> {
+        let mut request = HTTP_CLIENT
+            .get(format!("{}/lookup", self.lookup_url))
+            .query(&[("id", id), ("country", storefront)]);
+
+        if with_songs {
+            request = request.query(&[("entity", "song")]);
+        }
+
+        let response: LookupResponse = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|_| ParrotError::Other("failed to fetch track"))?
+            .json()
+            .await
+            .map_err(|_| ParrotError::Message(APPLE_MUSIC_INVALID_QUERY))?;
+
+        // albums are returned as a collection entry followed by one entry per song
+        let query_list = response
+            .results
+            .iter()
+            .filter(|result| result.wrapper_type == "track")
+            .filter_map(|result| {
+                let artist = result.artist_name.as_ref()?;
+                let track_name = result.track_name.as_ref()?;
+                Some(Self::build_query(artist, track_name))
+            })
+            .collect();
+
+        Ok(query_list)
+    }
+
+    fn build_query(artist: &str, track_name: &str) -> String {
+        format!("{} - {}", artist, track_name)
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    ffi::OsStr,
+    fs,
+    io::{Error as IoError, ErrorKind},
+    path::PathBuf,
+    process::Stdio,
+    sync::Mutex,
+    time::UNIX_EPOCH,
+};
+
+use lazy_static::lazy_static;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use songbird::tracks::TrackHandle;
+use tokio::process::Command as TokioCommand;
+
+use crate::{config::YTDL, sources::metadata_cache::CachedMetadata};
+
+const NEWLINE_BYTE: u8 = 0xA;
+
+const AUDIO_EXTENSION: &str = "audio";
+const METADATA_EXTENSION: &str = "json";
+const DOWNLOAD_EXTENSION: &str = "download";
+
+const DEFAULT_AUDIO_CACHE_SIZE_MB: u64 = 1024;
+const DEFAULT_AUDIO_CACHE_PREFETCH: usize = 2;
+
+/// Fields of yt-dlp's output that are too large to be worth keeping next to the audio.
+const DISCARDED_FIELDS: [&str; 6] = [
+    "formats",
+    "requested_formats",
+    "thumbnails",
+    "subtitles",
+    "automatic_captions",
+    "heatmap",
+];
+
+lazy_static! {
+    /// The cache is opt-in, it's only enabled when `AUDIO_CACHE_PATH` is set.
+    pub static ref AUDIO_CACHE: Option<AudioCache> = AudioCache::from_env();
+}
+
+/// An on-disk cache of downloaded tracks, bounded by size and evicted least recently used
+/// first. Upcoming tracks are prefetched in the background, so by the time they reach the
+/// front of the queue they play from disk instead of the network.
+pub struct AudioCache {
+    dir: PathBuf,
+    max_size: u64,
+    prefetch: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    downloading: HashSet<String>,
+    size: u64,
+    clock: u64,
+}
+
+struct CacheEntry {
+    size: u64,
+    last_used: u64,
+}
+
+impl AudioCache {
+    fn from_env() -> Option<AudioCache> {
+        let dir = env::var("AUDIO_CACHE_PATH").ok()?;
+
+        let max_size_mb = env::var("AUDIO_CACHE_SIZE_MB")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(DEFAULT_AUDIO_CACHE_SIZE_MB);
+
+        let prefetch = env::var("AUDIO_CACHE_PREFETCH")
+            .ok()
+            .and_then(|prefetch| prefetch.parse().ok())
+            .unwrap_or(DEFAULT_AUDIO_CACHE_PREFETCH);
+
+        match AudioCache::new(dir, max_size_mb * 1024 * 1024, prefetch) {
+            Ok(cache) => Some(cache),
+            Err(err) => {
+                tracing::error!(err = ?err, "Failed to open audio cache, continuing without it");
+                None
+            }
+        }
+    }
+
+    /// Opens the cache at the given directory, picking up the tracks downloaded by previous
+    /// runs. Their modification time stands in for the last time they were played.
+    pub fn new(dir: impl Into<PathBuf>, max_size: u64, prefetch: usize) -> Result<Self, IoError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut found = vec![];
+
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+
+            let Some(key) = path.file_stem().and_then(OsStr::to_str) else {
+                continue;
+            };
+
+            match path.extension().and_then(OsStr::to_str) {
+                Some(AUDIO_EXTENSION) if dir.join(metadata_file(key)).exists() => {
+                    let file = fs::metadata(&path)?;
+                    let modified = file.modified().unwrap_or(UNIX_EPOCH);
+                    found.push((key.to_string(), file.len(), modified));
+                }
+                // leftovers of downloads that were interrupted by a restart
+                Some(AUDIO_EXTENSION | DOWNLOAD_EXTENSION) => {
+                    fs::remove_file(&path).ok();
+                }
+                Some(METADATA_EXTENSION) if !dir.join(audio_file(key)).exists() => {
+                    fs::remove_file(&path).ok();
+                }
+                _ => {}
+            }
+        }
+
+        found.sort_by_key(|(_, _, modified)| *modified);
+
+        let mut state = CacheState::default();
+        for (key, size, _) in found {
+            state.clock += 1;
+            state.size += size;
+            state.entries.insert(
+                key,
+                CacheEntry {
+                    size,
+                    last_used: state.clock,
+                },
+            );
+        }
+
+        let cache = AudioCache {
+            dir,
+            max_size,
+            prefetch,
+            state: Mutex::new(state),
+        };
+
+        cache.evict(&mut cache.state.lock().unwrap());
+        Ok(cache)
+    }
+
+    /// Returns the file and metadata of a cached track, marking it as recently used.
+    pub fn get(&self, url: &str) -> Option<(PathBuf, CachedMetadata)> {
+        let key = cache_key(url);
+
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        state.entries.get_mut(&key)?.last_used = clock;
+        drop(state);
+
+        let metadata = fs::read(self.dir.join(metadata_file(&key))).ok()?;
+        let metadata = serde_json::from_slice(&metadata).ok()?;
+
+        Some((
+            self.dir.join(audio_file(&key)),
+            CachedMetadata::from_ytdl_output(metadata),
+        ))
+    }
+
+    /// Downloads the track in the background, unless it's cached or already being downloaded.
+    pub fn prefetch(&'static self, url: String) {
+        let key = cache_key(&url);
+
+        let mut state = self.state.lock().unwrap();
+        if state.entries.contains_key(&key) || !state.downloading.insert(key.clone()) {
+            return;
+        }
+        drop(state);
+
+        tokio::spawn(async move {
+            if let Err(err) = self.download(&url, &key).await {
+                tracing::warn!(err = ?err, url = %url, "Failed to prefetch track");
+                fs::remove_file(self.dir.join(download_file(&key))).ok();
+            }
+
+            self.state.lock().unwrap().downloading.remove(&key);
+        });
+    }
+
+    async fn download(&self, url: &str, key: &str) -> Result<(), IoError> {
+        let download_path = self.dir.join(download_file(key));
+
+        let ytdl_args = [
+            "-j",            // print JSON information for video for metadata
+            "-q",            // don't print progress logs
+            "--no-simulate", // ensure video is downloaded regardless of printing
+            "-f",
+            YTDL.format.as_str(), // select best quality audio-only
+            "-R",
+            "infinite",        // infinite number of download retries
+            "--no-playlist",   // only download the video if URL also has playlist info
+            "--ignore-config", // disable all configuration files for a yt-dlp run
+            "--no-warnings",   // don't print out warnings
+            "--no-part",       // the download is renamed once complete, no need for .part files
+            url,
+            "-o",
+        ];
+
+        let output = TokioCommand::new(&YTDL.path)
+            .args(ytdl_args)
+            .arg(&download_path)
+            .args(&YTDL.args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(IoError::new(
+                ErrorKind::Other,
+                format!("yt-dlp exited with {}", output.status),
+            ));
+        }
+
+        // when downloading to a file, yt-dlp prints the track info json to stdout instead
+        let end = output
+            .stdout
+            .iter()
+            .position(|el| *el == NEWLINE_BYTE)
+            .unwrap_or(output.stdout.len());
+
+        let mut metadata: Value = serde_json::from_slice(&output.stdout[..end])?;
+        if let Some(fields) = metadata.as_object_mut() {
+            DISCARDED_FIELDS.iter().for_each(|field| {
+                fields.remove(*field);
+            });
+        }
+
+        fs::write(
+            self.dir.join(metadata_file(key)),
+            serde_json::to_vec(&metadata)?,
+        )?;
+
+        let size = fs::metadata(&download_path)?.len();
+        fs::rename(&download_path, self.dir.join(audio_file(key)))?;
+
+        self.insert(key, size);
+        Ok(())
+    }
+
+    /// Keeps track of a track whose files are in place, making room for it by evicting the
+    /// least recently used tracks once the cache grows past its size. A track that was
+    /// already cached is replaced, along with its size.
+    pub fn insert(&self, key: &str, size: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        state.size += size;
+        let last_used = state.clock;
+
+        let previous = state
+            .entries
+            .insert(key.to_string(), CacheEntry { size, last_used });
+        if let Some(previous) = previous {
+            state.size -= previous.size;
+        }
+
+        self.evict(&mut state);
+    }
+
+    /// How many bytes the cached tracks take up.
+    pub fn size(&self) -> u64 {
+        self.state.lock().unwrap().size
+    }
+
+    fn evict(&self, state: &mut CacheState) {
+        while state.size > self.max_size {
+            let Some(key) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+
+            let entry = state.entries.remove(&key).unwrap();
+            state.size -= entry.size;
+
+            // tracks being played keep their open file handle, so it's safe to remove them
+            fs::remove_file(self.dir.join(audio_file(&key))).ok();
+            fs::remove_file(self.dir.join(metadata_file(&key))).ok();
+        }
+    }
+}
+
+/// Prefetches the tracks queued right after the one that's playing, if the cache is enabled.
+pub fn prefetch_queue(queue: &[TrackHandle]) {
+    let Some(cache) = AUDIO_CACHE.as_ref() else {
+        return;
+    };
+
+    queue
+        .iter()
+        .skip(1)
+        .take(cache.prefetch)
+        .filter_map(|track| track.metadata().source_url.clone())
+        .for_each(|url| cache.prefetch(url));
+}
+
+/// Names the files of a track, with a hash that stays the same across Rust releases so
+/// tracks cached by previous runs are still found.
+pub fn cache_key(url: &str) -> String {
+    Sha256::digest(url.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn audio_file(key: &str) -> String {
+    format!("{key}.{AUDIO_EXTENSION}")
+}
+
+fn metadata_file(key: &str) -> String {
+    format!("{key}.{METADATA_EXTENSION}")
+}
+
+fn download_file(key: &str) -> String {
+    format!("{key}.{DOWNLOAD_EXTENSION}")
+}
@@ -0,0 +1,148 @@
+use std::{env, str::FromStr};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    commands::play::QueryType,
+    errors::ParrotError,
+    messaging::messages::{DEEZER_INVALID_QUERY, DEEZER_PLAYLIST_FAILED},
+    sources::HTTP_CLIENT,
+};
+
+const DEFAULT_DEEZER_API_URL: &str = "https://api.deezer.com";
+
+lazy_static! {
+    pub static ref DEEZER: Deezer =
+        Deezer::new(env::var("DEEZER_API_URL").unwrap_or(DEFAULT_DEEZER_API_URL.to_string()));
+    pub static ref DEEZER_QUERY_REGEX: Regex =
+        Regex::new(r"deezer.com/(?:[a-z]{2}/)?(?P<media_type>[a-z]+)/(?P<media_id>\d+)").unwrap();
+}
+
+#[derive(Clone, Copy)]
+pub enum MediaType {
+    Track,
+    Album,
+    Playlist,
+}
+
+impl FromStr for MediaType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "track" => Ok(Self::Track),
+            "album" => Ok(Self::Album),
+            "playlist" => Ok(Self::Playlist),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DeezerTrack {
+    title: String,
+    artist: DeezerArtist,
+}
+
+#[derive(Deserialize)]
+struct DeezerArtist {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct DeezerTrackList {
+    tracks: DeezerData,
+}
+
+#[derive(Deserialize)]
+struct DeezerData {
+    data: Vec<DeezerTrack>,
+}
+
+pub struct Deezer {
+    api_url: String,
+}
+
+impl Deezer {
+    pub fn new(api_url: String) -> Deezer {
+        Deezer { api_url }
+    }
+
+    pub async fn extract(&self, query: &str) -> Result<QueryType, ParrotError> {
+        // short links only redirect to the actual page, so follow them to learn where they point
+        let query = if query.contains("deezer.page.link") || query.contains("link.deezer.com") {
+            let response = HTTP_CLIENT
+                .get(query)
+                .send()
+                .await
+                .map_err(|_| ParrotError::Message(DEEZER_INVALID_QUERY))?;
+
+            response.url().to_string()
+        } else {
+            query.to_string()
+        };
+
+        let captures = DEEZER_QUERY_REGEX
+            .captures(&query)
+            .ok_or(ParrotError::Message(DEEZER_INVALID_QUERY))?;
+
+        let media_type = captures
+            .name("media_type")
+            .ok_or(ParrotError::Message(DEEZER_INVALID_QUERY))?
+            .as_str();
+
+        let media_type = MediaType::from_str(media_type)
+            .map_err(|_| ParrotError::Message(DEEZER_INVALID_QUERY))?;
+
+        let media_id = captures
+            .name("media_id")
+            .ok_or(ParrotError::Message(DEEZER_INVALID_QUERY))?
+            .as_str();
+
+        match media_type {
+            MediaType::Track => self.get_track_info(media_id).await,
+            MediaType::Album => self.get_track_list_info("album", media_id).await,
+            MediaType::Playlist => self.get_track_list_info("playlist", media_id).await,
+        }
+    }
+
+    async fn get_track_info(&self, id: &str) -> Result<QueryType, ParrotError> {
+        let track: DeezerTrack = HTTP_CLIENT
+            .get(format!("{}/track/{}", self.api_url, id))
+            .send()
+            .await
+            .map_err(|_| ParrotError::Other("failed to fetch track"))?
+            .json()
+            .await
+            .map_err(|_| ParrotError::Message(DEEZER_INVALID_QUERY))?;
+
+        let query = Self::build_query(&track.artist.name, &track.title);
+        Ok(QueryType::Keywords(query))
+    }
+
+    async fn get_track_list_info(&self, kind: &str, id: &str) -> Result<QueryType, ParrotError> {
+        let list: DeezerTrackList = HTTP_CLIENT
+            .get(format!("{}/{}/{}", self.api_url, kind, id))
+            .send()
+            .await
+            .map_err(|_| ParrotError::Message(DEEZER_PLAYLIST_FAILED))?
+            .json()
+            .await
+            .map_err(|_| ParrotError::Message(DEEZER_PLAYLIST_FAILED))?;
+
+        let query_list: Vec<String> = list
+            .tracks
+            .data
+            .iter()
+            .map(|track| Self::build_query(&track.artist.name, &track.title))
+            .collect();
+
+        Ok(QueryType::KeywordList(query_list))
+    }
+
+    fn build_query(artist: &str, track_name: &str) -> String {
+        format!("{} - {}", artist, track_name)
+    }
+}
@@ -1,44 +1,153 @@
-use std::process::{Child, Command, Stdio};
+use std::{
+    path::Path,
+    process::{Child, Command, Stdio},
+};
 
 use songbird::input::{
+    codec::OpusDecoderState,
     error::{Error, Result},
-    Codec, Container, Input, Metadata, Reader,
+    ChildContainer, Codec, Container, Input, Metadata, Reader,
+};
+
+use crate::{
+    config::FFMPEG,
+    sources::{metadata_cache::CachedMetadata, ogg::OggOpusReader},
 };
 
-pub async fn ffmpeg(mut source: Child, metadata: Metadata, pre_args: &[&str]) -> Result<Input> {
-    let ffmpeg_args = [
-        "-i",
-        "-", // read from stdout
-        "-f",
-        "s16le", // use PCM signed 16-bit little-endian format
-        "-ac",
-        "2", // set two audio channels
-        "-ar",
-        "48000", // set audio sample rate of 48000Hz
-        "-acodec",
-        "pcm_f32le",
-        "-",
-    ];
+const FFMPEG_PCM_ARGS: [&str; 9] = [
+    "-f",
+    "f32le", // use PCM 32-bit float little-endian format
+    "-ac",
+    "2", // set two audio channels
+    "-ar",
+    "48000", // set audio sample rate of 48000Hz
+    "-acodec",
+    "pcm_f32le",
+    "-",
+];
+
+const FFMPEG_OPUS_ARGS: [&str; 5] = [
+    "-vn", // drop any video stream
+    "-f",
+    "ogg", // wrap the Opus packets in Ogg, which the reader then unwraps
+    "-map_metadata",
+    "-1", // don't bother writing tags
+];
+
+const FFMPEG_OPUS_COPY_ARGS: [&str; 2] = ["-c:a", "copy"];
+
+const FFMPEG_OPUS_TRANSCODE_ARGS: [&str; 8] = [
+    "-c:a", "libopus", "-ac", "2", "-ar", "48000", "-b:a", "128k",
+];
+
+/// How the audio of a track is handed to songbird.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Decoded to PCM by ffmpeg, songbird then encodes it to Opus.
+    Pcm,
+    /// Handed over as Opus packets, which songbird can send without decoding when nothing
+    /// alters the audio. The stream is only transcoded if it isn't Opus already.
+    Opus { transcode: bool },
+}
+
+impl Encoding {
+    /// Adjusts the encoding picked when the track was created to the stream that's actually
+    /// being played, yt-dlp may select a different format by then.
+    pub fn for_stream(&self, metadata: &CachedMetadata) -> Encoding {
+        match self {
+            Self::Pcm => Self::Pcm,
+            Self::Opus { .. } => Self::Opus {
+                transcode: metadata.acodec.as_deref() != Some("opus"),
+            },
+        }
+    }
+
+    /// Codec and container songbird reads the input with, for the given encoding.
+    pub fn codec(&self) -> Result<(Codec, Container)> {
+        match self {
+            Self::Pcm => Ok((Codec::FloatPcm, Container::Raw)),
+            Self::Opus { .. } => Ok((
+                Codec::Opus(OpusDecoderState::new()?),
+                Container::Dca { first_frame: 0 },
+            )),
+        }
+    }
+
+    fn args(&self) -> Vec<&str> {
+        match self {
+            Self::Pcm => FFMPEG
+                .args
+                .iter()
+                .map(String::as_str)
+                .chain(FFMPEG_PCM_ARGS)
+                .collect(),
+            Self::Opus { transcode } => {
+                let codec_args = if *transcode {
+                    &FFMPEG_OPUS_TRANSCODE_ARGS[..]
+                } else {
+                    &FFMPEG_OPUS_COPY_ARGS[..]
+                };
 
+                codec_args
+                    .iter()
+                    .chain(FFMPEG_OPUS_ARGS.iter())
+                    .copied()
+                    .chain(["-"])
+                    .collect()
+            }
+        }
+    }
+
+    fn input(&self, children: Vec<Child>, metadata: Metadata) -> Result<Input> {
+        let (codec, container) = self.codec()?;
+
+        let reader = match self {
+            Self::Pcm => Reader::from(children),
+            Self::Opus { .. } => {
+                Reader::Extension(Box::new(OggOpusReader::new(ChildContainer::from(children))))
+            }
+        };
+
+        Ok(Input::new(true, reader, codec, container, Some(metadata)))
+    }
+}
+
+pub async fn ffmpeg(
+    mut source: Child,
+    metadata: Metadata,
+    pre_args: &[&str],
+    encoding: Encoding,
+) -> Result<Input> {
     let taken_stdout = source.stdout.take().ok_or(Error::Stdout)?;
 
-    let ffmpeg = Command::new("ffmpeg")
+    let ffmpeg = Command::new(&FFMPEG.path)
         .args(pre_args)
-        .args(ffmpeg_args)
+        .args(["-i", "-"]) // read from stdout
+        .args(encoding.args())
         .stdin(taken_stdout)
         .stderr(Stdio::null())
         .stdout(Stdio::piped())
         .spawn()?;
 
-    let reader = Reader::from(vec![source, ffmpeg]);
+    encoding.input(vec![source, ffmpeg], metadata)
+}
 
-    let input = Input::new(
-        true,
-        reader,
-        Codec::FloatPcm,
-        Container::Raw,
-        Some(metadata),
-    );
+/// Same as [`ffmpeg`], but reads the track from a file of the audio cache.
+pub async fn ffmpeg_file(
+    path: &Path,
+    metadata: Metadata,
+    pre_args: &[&str],
+    encoding: Encoding,
+) -> Result<Input> {
+    let ffmpeg = Command::new(&FFMPEG.path)
+        .args(pre_args)
+        .arg("-i")
+        .arg(path)
+        .args(encoding.args())
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()?;
 
-    Ok(input)
+    encoding.input(vec![ffmpeg], metadata)
 }
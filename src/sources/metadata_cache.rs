@@ -0,0 +1,296 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::Error as IoError,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use songbird::input::Metadata;
+use tokio::task;
+
+const DEFAULT_METADATA_CACHE_TTL_HOURS: u64 = 24 * 7;
+const DEFAULT_METADATA_CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// How long inserts are gathered before the cache is written to disk, so queueing a
+/// playlist rewrites the file once rather than once per track.
+const PERSIST_DELAY: Duration = Duration::from_secs(10);
+
+/// Bumped whenever entries gain fields that can't be left out, so entries persisted before
+/// are looked up again instead of e.g. slipping past the content filter.
+pub const METADATA_CACHE_FORMAT: u32 = 1;
+
+lazy_static! {
+    /// Lookups are always cached in memory, `METADATA_CACHE_PATH` also persists them to disk.
+    pub static ref METADATA_CACHE: MetadataCache = MetadataCache::from_env();
+}
+
+/// A cache of yt-dlp lookups, so tracks that are queued often resolve without spawning
+/// yt-dlp. Entries are keyed both by the URL and by the normalized search query that
+/// resolved to them, and expire after a while since videos get taken down or edited.
+/// Past the maximum number of entries, the oldest ones are dropped first.
+pub struct MetadataCache {
+    path: Option<PathBuf>,
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, CachedMetadata>>,
+    persist_pending: AtomicBool,
+    write_lock: Mutex<()>,
+}
+
+/// The parts of yt-dlp's output we care about, entries written by older versions miss
+/// the newer fields so they fall back to their defaults, or are dropped on load when
+/// they predate [`METADATA_CACHE_FORMAT`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CachedMetadata {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub channel: Option<String>,
+    pub date: Option<String>,
+    pub duration: Option<Duration>,
+    pub thumbnail: Option<String>,
+    pub source_url: Option<String>,
+    /// Codec of the audio stream yt-dlp selects, e.g. `opus`.
+    pub acodec: Option<String>,
+    pub chapters: Vec<Chapter>,
+    /// The minimum age to watch the video, zero or missing when there's none.
+    pub age_limit: Option<u64>,
+    /// Categories of the video, e.g. `Music`, not every site has them.
+    pub categories: Vec<String>,
+    cached_at: u64,
+    format: u32,
+}
+
+/// A chapter of a video, as listed by yt-dlp with its bounds in seconds.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+impl Chapter {
+    pub fn start(&self) -> Duration {
+        Duration::from_secs_f64(self.start_time.max(0.0))
+    }
+}
+
+impl CachedMetadata {
+    pub fn from_ytdl_output(value: Value) -> Self {
+        let acodec = value
+            .get("acodec")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        let chapters = value
+            .get("chapters")
+            .cloned()
+            .and_then(|chapters| serde_json::from_value(chapters).ok())
+            .unwrap_or_default();
+
+        let age_limit = value.get("age_limit").and_then(Value::as_u64);
+
+        let categories = value
+            .get("categories")
+            .cloned()
+            .and_then(|categories| serde_json::from_value(categories).ok())
+            .unwrap_or_default();
+
+        let metadata = Metadata::from_ytdl_output(value);
+
+        Self {
+            title: metadata.title,
+            artist: metadata.artist,
+            channel: metadata.channel,
+            date: metadata.date,
+            duration: metadata.duration,
+            thumbnail: metadata.thumbnail,
+            source_url: metadata.source_url,
+            acodec,
+            chapters,
+            age_limit,
+            categories,
+            cached_at: now(),
+            format: METADATA_CACHE_FORMAT,
+        }
+    }
+
+    fn is_expired(&self, ttl: Duration) -> bool {
+        now().saturating_sub(self.cached_at) > ttl.as_secs()
+    }
+
+    fn is_outdated(&self) -> bool {
+        self.format < METADATA_CACHE_FORMAT
+    }
+
+    pub fn to_metadata(&self) -> Metadata {
+        Metadata {
+            title: self.title.clone(),
+            artist: self.artist.clone(),
+            channel: self.channel.clone(),
+            date: self.date.clone(),
+            duration: self.duration,
+            thumbnail: self.thumbnail.clone(),
+            source_url: self.source_url.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl MetadataCache {
+    fn from_env() -> MetadataCache {
+        let ttl_hours = env::var("METADATA_CACHE_TTL_HOURS")
+            .ok()
+            .and_then(|ttl| ttl.parse().ok())
+            .unwrap_or(DEFAULT_METADATA_CACHE_TTL_HOURS);
+
+        let max_entries = env::var("METADATA_CACHE_MAX_ENTRIES")
+            .ok()
+            .and_then(|max_entries| max_entries.parse().ok())
+            .unwrap_or(DEFAULT_METADATA_CACHE_MAX_ENTRIES);
+
+        let path = env::var("METADATA_CACHE_PATH").ok().map(PathBuf::from);
+        MetadataCache::new(path, Duration::from_secs(ttl_hours * 60 * 60), max_entries)
+    }
+
+    /// Creates the cache, loading the entries persisted at the given path that haven't expired
+    /// and are still in the current format.
+    pub fn new(path: Option<PathBuf>, ttl: Duration, max_entries: usize) -> MetadataCache {
+        let mut entries: HashMap<String, CachedMetadata> = path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|contents| serde_json::from_slice(&contents).ok())
+            .unwrap_or_default();
+
+        entries.retain(|_, entry| !entry.is_outdated());
+        prune(&mut entries, ttl, max_entries);
+
+        MetadataCache {
+            path,
+            ttl,
+            max_entries,
+            entries: Mutex::new(entries),
+            persist_pending: AtomicBool::new(false),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Looks up the metadata of an URL or of a `ytsearch:` query.
+    pub fn get(&self, uri: &str) -> Option<CachedMetadata> {
+        let key = cache_key(uri);
+        let mut entries = self.entries.lock().unwrap();
+
+        let entry = entries.get(&key)?.clone();
+        if entry.is_expired(self.ttl) {
+            entries.remove(&key);
+            return None;
+        }
+
+        Some(entry)
+    }
+
+    /// Caches the metadata under the URI that was looked up, and under the URL it resolved
+    /// to so that later lookups of the same track by URL also hit.
+    pub fn insert(&'static self, uri: &str, metadata: &CachedMetadata) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(source_url) = &metadata.source_url {
+            entries.insert(cache_key(source_url), metadata.clone());
+        }
+        entries.insert(cache_key(uri), metadata.clone());
+        prune(&mut entries, self.ttl, self.max_entries);
+        drop(entries);
+
+        self.persist();
+    }
+
+    /// Writes the cache to disk in the background once inserts settle down, replacing the
+    /// file atomically so a crash midway never leaves a truncated cache behind.
+    fn persist(&'static self) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+
+        // a write is already scheduled and will pick up this insert too
+        if self.persist_pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        task::spawn(async move {
+            tokio::time::sleep(PERSIST_DELAY).await;
+            self.persist_pending.store(false, Ordering::Release);
+
+            task::spawn_blocking(move || self.write(path)).await.ok();
+        });
+    }
+
+    fn write(&self, path: &Path) {
+        let _guard = self.write_lock.lock().unwrap();
+
+        let result = (|| -> Result<(), IoError> {
+            let contents = serde_json::to_vec(&*self.entries.lock().unwrap())?;
+
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+
+            let temp_path = path.with_extension("tmp");
+            fs::write(&temp_path, contents)?;
+            fs::rename(&temp_path, path)
+        })();
+
+        if let Err(err) = result {
+            tracing::warn!(err = ?err, "Failed to persist metadata cache");
+        }
+    }
+}
+
+/// Drops the expired entries, then the oldest ones until the cache is back to its size.
+fn prune(entries: &mut HashMap<String, CachedMetadata>, ttl: Duration, max_entries: usize) {
+    entries.retain(|_, entry| !entry.is_expired(ttl));
+
+    if entries.len() <= max_entries {
+        return;
+    }
+
+    let mut by_age = entries
+        .iter()
+        .map(|(key, entry)| (entry.cached_at, key.clone()))
+        .collect::<Vec<_>>();
+    by_age.sort();
+
+    let excess = entries.len() - max_entries;
+    for (_, key) in by_age.into_iter().take(excess) {
+        entries.remove(&key);
+    }
+}
+
+/// Searches are keyed by their normalized query, so differences in casing or spacing
+/// between requests for the same song still hit the cache.
+pub fn cache_key(uri: &str) -> String {
+    match uri.strip_prefix("ytsearch:") {
+        Some(query) => format!(
+            "search:{}",
+            query
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase()
+        ),
+        None => format!("url:{}", uri.trim()),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
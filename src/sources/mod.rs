@@ -1,3 +1,18 @@
+use lazy_static::lazy_static;
+use reqwest::Client;
+
+pub mod apple_music;
+pub mod audio_cache;
+pub mod deezer;
 pub mod ffmpeg;
+pub mod metadata_cache;
+pub mod ogg;
+pub mod provider;
 pub mod spotify;
+pub mod tidal;
 pub mod youtube;
+
+lazy_static! {
+    /// HTTP client shared by the resolvers of the streaming services without an SDK.
+    pub static ref HTTP_CLIENT: Client = Client::new();
+}
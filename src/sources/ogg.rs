@@ -0,0 +1,107 @@
+use std::{
+    collections::VecDeque,
+    io::{ErrorKind, Read, Result},
+};
+
+const PAGE_HEADER_LEN: usize = 27;
+const CAPTURE_PATTERN: &[u8; 4] = b"OggS";
+
+/// Extracts the Opus packets of an Ogg stream and frames them the way songbird's DCA
+/// container expects, a little-endian `i16` length followed by the packet itself. This lets
+/// songbird send the packets to Discord as they are instead of decoding and re-encoding them.
+pub struct OggOpusReader<R> {
+    inner: R,
+    packets: VecDeque<Vec<u8>>,
+    partial: Vec<u8>,
+    frame: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> OggOpusReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            packets: VecDeque::new(),
+            partial: vec![],
+            frame: vec![],
+            pos: 0,
+        }
+    }
+
+    fn next_packet(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            if let Some(packet) = self.packets.pop_front() {
+                return Ok(Some(packet));
+            }
+
+            if !self.read_page()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Reads a whole page, splitting its segments into packets. Packets that continue on
+    /// the next page are kept aside until they're complete.
+    fn read_page(&mut self) -> Result<bool> {
+        let mut header = [0u8; PAGE_HEADER_LEN];
+        match self.inner.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(false),
+            Err(err) => return Err(err),
+        }
+
+        if &header[..4] != CAPTURE_PATTERN {
+            return Err(ErrorKind::InvalidData.into());
+        }
+
+        let mut lacing = vec![0u8; header[26] as usize];
+        self.inner.read_exact(&mut lacing)?;
+
+        let mut data = vec![0u8; lacing.iter().map(|&lace| lace as usize).sum()];
+        self.inner.read_exact(&mut data)?;
+
+        let mut offset = 0;
+        for lace in lacing {
+            let lace = lace as usize;
+            self.partial.extend_from_slice(&data[offset..offset + lace]);
+            offset += lace;
+
+            // a lacing value below 255 marks the end of a packet
+            if lace < 255 {
+                self.packets.push_back(std::mem::take(&mut self.partial));
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for OggOpusReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        while self.pos >= self.frame.len() {
+            let Some(packet) = self.next_packet()? else {
+                return Ok(0);
+            };
+
+            // the headers describe the stream, they aren't audio
+            if packet.is_empty()
+                || packet.starts_with(b"OpusHead")
+                || packet.starts_with(b"OpusTags")
+            {
+                continue;
+            }
+
+            self.frame.clear();
+            self.frame
+                .extend_from_slice(&(packet.len() as i16).to_le_bytes());
+            self.frame.extend_from_slice(&packet);
+            self.pos = 0;
+        }
+
+        let len = usize::min(buf.len(), self.frame.len() - self.pos);
+        buf[..len].copy_from_slice(&self.frame[self.pos..self.pos + len]);
+        self.pos += len;
+
+        Ok(len)
+    }
+}
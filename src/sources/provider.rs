@@ -0,0 +1,108 @@
+use url::Url;
+
+use crate::commands::play::QueryType;
+
+/// The streaming services yt-dlp knows how to extract from which we route explicitly.
+/// Anything else is handed to yt-dlp as a single video through [`Provider::Generic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Provider {
+    YouTube,
+    SoundCloud,
+    Bandcamp,
+    Vimeo,
+    Twitch,
+    Generic,
+}
+
+impl Provider {
+    pub fn from_url(url: &Url) -> Provider {
+        let Some(host) = url.host_str() else {
+            return Self::Generic;
+        };
+
+        let host = host.trim_start_matches("www.").trim_start_matches("m.");
+
+        match host {
+            "youtube.com" | "music.youtube.com" | "youtu.be" => Self::YouTube,
+            "soundcloud.com" | "on.soundcloud.com" => Self::SoundCloud,
+            "vimeo.com" | "player.vimeo.com" => Self::Vimeo,
+            "twitch.tv" | "clips.twitch.tv" => Self::Twitch,
+            _ if host == "bandcamp.com" || host.ends_with(".bandcamp.com") => Self::Bandcamp,
+            _ => Self::Generic,
+        }
+    }
+
+    /// Parses the query as an URL and decides whether it points to a single track or to a
+    /// collection of them, according to the URL layout of the service it belongs to.
+    pub fn extract(query: &str) -> Option<QueryType> {
+        let url = Url::parse(query).ok()?;
+
+        if Self::from_url(&url).is_playlist(&url) {
+            Some(QueryType::PlaylistLink(query.to_string()))
+        } else {
+            Some(QueryType::VideoLink(query.to_string()))
+        }
+    }
+
+    pub fn is_playlist(&self, url: &Url) -> bool {
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+
+        match self {
+            Self::YouTube => {
+                segments.first() == Some(&"playlist")
+                    || url.query_pairs().any(|(key, _)| key == "list")
+            }
+            // soundcloud.com/<user>, soundcloud.com/<user>/sets/<set> and the user's tabs
+            Self::SoundCloud => match segments.as_slice() {
+                [_user] => url.host_str() != Some("on.soundcloud.com"),
+                [_user, "sets", ..] => true,
+                [_user, "tracks" | "albums" | "likes" | "reposts" | "popular-tracks"] => true,
+                _ => false,
+            },
+            // <artist>.bandcamp.com, <artist>.bandcamp.com/music and <artist>.bandcamp.com/album/<album>
+            Self::Bandcamp => matches!(segments.as_slice(), [] | ["music"] | ["album", ..]),
+            // vimeo.com/<id> is a video, almost everything else is a collection of them
+            Self::Vimeo => match segments.as_slice() {
+                [id] => !is_numeric(id),
+                ["showcase" | "album" | "groups", ..] => true,
+                ["channels", _channel] => true,
+                [_user, "videos" | "likes"] => true,
+                _ => false,
+            },
+            // twitch.tv/<channel>/videos lists the channel's VODs
+            Self::Twitch => matches!(segments.as_slice(), [_channel, "videos"]),
+            Self::Generic => false,
+        }
+    }
+
+    /// Human friendly name of the service, or `None` for generic sources.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::YouTube => Some("YouTube"),
+            Self::SoundCloud => Some("SoundCloud"),
+            Self::Bandcamp => Some("Bandcamp"),
+            Self::Vimeo => Some("Vimeo"),
+            Self::Twitch => Some("Twitch"),
+            Self::Generic => None,
+        }
+    }
+
+    /// Domain whose favicon best represents the service, ignoring mirrors and short links.
+    pub fn icon_domain(&self) -> Option<&'static str> {
+        match self {
+            Self::YouTube => Some("youtube.com"),
+            Self::SoundCloud => Some("soundcloud.com"),
+            Self::Bandcamp => Some("bandcamp.com"),
+            Self::Vimeo => Some("vimeo.com"),
+            Self::Twitch => Some("twitch.tv"),
+            Self::Generic => None,
+        }
+    }
+}
+
+fn is_numeric(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit())
+}
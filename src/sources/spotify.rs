@@ -4,7 +4,10 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use rspotify::{
     clients::BaseClient,
-    model::{AlbumId, PlayableItem, PlaylistId, SimplifiedArtist, TrackId},
+    model::{
+        AlbumId, ArtistId, Country, EpisodeId, FullEpisode, Market, PlayableItem, PlaylistId,
+        ShowId, SimplifiedArtist, TrackId,
+    },
     ClientCredsSpotify, Credentials,
 };
 use tokio::sync::Mutex;
@@ -22,11 +25,17 @@ lazy_static! {
         Regex::new(r"spotify.com/(?P<media_type>.+)/(?P<media_id>.*?)(?:\?|$)").unwrap();
 }
 
+/// Market used for requests where Spotify requires one to pick the available content.
+const SPOTIFY_MARKET: Market = Market::Country(Country::UnitedStates);
+
 #[derive(Clone, Copy)]
 pub enum MediaType {
     Track,
     Album,
     Playlist,
+    Artist,
+    Show,
+    Episode,
 }
 
 impl FromStr for MediaType {
@@ -37,6 +46,9 @@ impl FromStr for MediaType {
             "track" => Ok(Self::Track),
             "album" => Ok(Self::Album),
             "playlist" => Ok(Self::Playlist),
+            "artist" => Ok(Self::Artist),
+            "show" => Ok(Self::Show),
+            "episode" => Ok(Self::Episode),
             _ => Err(()),
         }
     }
@@ -66,25 +78,28 @@ impl Spotify {
     ) -> Result<QueryType, ParrotError> {
         let captures = SPOTIFY_QUERY_REGEX
             .captures(query)
-            .ok_or(ParrotError::Other(SPOTIFY_INVALID_QUERY))?;
+            .ok_or(ParrotError::Message(SPOTIFY_INVALID_QUERY))?;
 
         let media_type = captures
             .name("media_type")
-            .ok_or(ParrotError::Other(SPOTIFY_INVALID_QUERY))?
+            .ok_or(ParrotError::Message(SPOTIFY_INVALID_QUERY))?
             .as_str();
 
         let media_type = MediaType::from_str(media_type)
-            .map_err(|_| ParrotError::Other(SPOTIFY_INVALID_QUERY))?;
+            .map_err(|_| ParrotError::Message(SPOTIFY_INVALID_QUERY))?;
 
         let media_id = captures
             .name("media_id")
-            .ok_or(ParrotError::Other(SPOTIFY_INVALID_QUERY))?
+            .ok_or(ParrotError::Message(SPOTIFY_INVALID_QUERY))?
             .as_str();
 
         match media_type {
             MediaType::Track => Self::get_track_info(spotify, media_id).await,
             MediaType::Album => Self::get_album_info(spotify, media_id).await,
             MediaType::Playlist => Self::get_playlist_info(spotify, media_id).await,
+            MediaType::Artist => Self::get_artist_info(spotify, media_id).await,
+            MediaType::Show => Self::get_show_info(spotify, media_id).await,
+            MediaType::Episode => Self::get_episode_info(spotify, media_id).await,
         }
     }
 
@@ -140,28 +155,96 @@ impl Spotify {
         let playlist = spotify
             .playlist(playlist_id, None, None)
             .await
-            .map_err(|_| ParrotError::Other(SPOTIFY_PLAYLIST_FAILED))?;
+            .map_err(|_| ParrotError::Message(SPOTIFY_PLAYLIST_FAILED))?;
 
         let query_list: Vec<String> = playlist
             .tracks
             .items
             .iter()
-            .filter_map(|item| match item.track.as_ref().unwrap() {
+            .filter_map(|item| match item.track.as_ref()? {
                 PlayableItem::Track(track) => {
                     let artist_names = Self::join_artist_names(&track.album.artists);
                     Some(Self::build_query(&artist_names, &track.name))
                 }
-                PlayableItem::Episode(_) => None,
+                PlayableItem::Episode(episode) => Some(Self::build_episode_query(episode)),
             })
             .collect();
 
         Ok(QueryType::KeywordList(query_list))
     }
 
+    async fn get_artist_info(
+        spotify: &ClientCredsSpotify,
+        id: &str,
+    ) -> Result<QueryType, ParrotError> {
+        let artist_id = ArtistId::from_id(id)
+            .map_err(|_| ParrotError::Other("artist ID contains invalid characters"))?;
+
+        let tracks = spotify
+            .artist_top_tracks(artist_id, Some(SPOTIFY_MARKET))
+            .await
+            .map_err(|_| ParrotError::Other("failed to fetch artist"))?;
+
+        let query_list: Vec<String> = tracks
+            .iter()
+            .map(|track| {
+                let artist_names = Self::join_artist_names(&track.artists);
+                Self::build_query(&artist_names, &track.name)
+            })
+            .collect();
+
+        Ok(QueryType::KeywordList(query_list))
+    }
+
+    async fn get_show_info(
+        spotify: &ClientCredsSpotify,
+        id: &str,
+    ) -> Result<QueryType, ParrotError> {
+        let show_id = ShowId::from_id(id)
+            .map_err(|_| ParrotError::Other("show ID contains invalid characters"))?;
+
+        let show = spotify
+            .get_a_show(show_id, Some(SPOTIFY_MARKET))
+            .await
+            .map_err(|_| ParrotError::Other("failed to fetch show"))?;
+
+        // episodes are returned newest first, so queue them in the order they were released
+        let query_list: Vec<String> = show
+            .episodes
+            .items
+            .iter()
+            .rev()
+            .map(|episode| Self::build_query(&show.name, &episode.name))
+            .collect();
+
+        Ok(QueryType::KeywordList(query_list))
+    }
+
+    async fn get_episode_info(
+        spotify: &ClientCredsSpotify,
+        id: &str,
+    ) -> Result<QueryType, ParrotError> {
+        let episode_id = EpisodeId::from_id(id)
+            .map_err(|_| ParrotError::Other("episode ID contains invalid characters"))?;
+
+        let episode = spotify
+            .get_an_episode(episode_id, Some(SPOTIFY_MARKET))
+            .await
+            .map_err(|_| ParrotError::Other("failed to fetch episode"))?;
+
+        Ok(QueryType::Keywords(Self::build_episode_query(&episode)))
+    }
+
     fn build_query(artists: &str, track_name: &str) -> String {
         format!("{} - {}", artists, track_name)
     }
 
+    /// Spotify doesn't expose the audio of podcast episodes, but most podcasts are also
+    /// published on YouTube, so search for the episode there by its show's name.
+    fn build_episode_query(episode: &FullEpisode) -> String {
+        Self::build_query(&episode.show.name, &episode.name)
+    }
+
     fn join_artist_names(artists: &[SimplifiedArtist]) -> String {
         let artist_names: Vec<String> = artists.iter().map(|artist| artist.name.clone()).collect();
         artist_names.join(" ")
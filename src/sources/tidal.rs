@@ -0,0 +1,159 @@
+use std::{env, str::FromStr};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    commands::play::QueryType,
+    errors::ParrotError,
+    messaging::messages::{TIDAL_AUTH_FAILED, TIDAL_INVALID_QUERY},
+    sources::HTTP_CLIENT,
+};
+
+const DEFAULT_TIDAL_API_URL: &str = "https://api.tidal.com";
+const DEFAULT_TIDAL_COUNTRY_CODE: &str = "US";
+const TIDAL_PAGE_LIMIT: &str = "100";
+
+lazy_static! {
+    pub static ref TIDAL: Tidal = Tidal::new(
+        env::var("TIDAL_API_URL").unwrap_or(DEFAULT_TIDAL_API_URL.to_string()),
+        env::var("TIDAL_TOKEN").ok(),
+        env::var("TIDAL_COUNTRY_CODE").unwrap_or(DEFAULT_TIDAL_COUNTRY_CODE.to_string()),
+    );
+    pub static ref TIDAL_QUERY_REGEX: Regex =
+        Regex::new(r"tidal.com/(?:browse/)?(?P<media_type>[a-z]+)/(?P<media_id>[0-9a-f-]+)")
+            .unwrap();
+}
+
+#[derive(Clone, Copy)]
+pub enum MediaType {
+    Track,
+    Album,
+    Playlist,
+}
+
+impl FromStr for MediaType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "track" => Ok(Self::Track),
+            "album" => Ok(Self::Album),
+            "playlist" => Ok(Self::Playlist),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TidalTrack {
+    title: String,
+    artists: Vec<TidalArtist>,
+}
+
+#[derive(Deserialize)]
+struct TidalArtist {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TidalTrackList {
+    items: Vec<TidalTrack>,
+}
+
+pub struct Tidal {
+    api_url: String,
+    token: Option<String>,
+    country_code: String,
+}
+
+impl Tidal {
+    pub fn new(api_url: String, token: Option<String>, country_code: String) -> Tidal {
+        Tidal {
+            api_url,
+            token,
+            country_code,
+        }
+    }
+
+    pub async fn extract(&self, query: &str) -> Result<QueryType, ParrotError> {
+        let captures = TIDAL_QUERY_REGEX
+            .captures(query)
+            .ok_or(ParrotError::Message(TIDAL_INVALID_QUERY))?;
+
+        let media_type = captures
+            .name("media_type")
+            .ok_or(ParrotError::Message(TIDAL_INVALID_QUERY))?
+            .as_str();
+
+        let media_type = MediaType::from_str(media_type)
+            .map_err(|_| ParrotError::Message(TIDAL_INVALID_QUERY))?;
+
+        let media_id = captures
+            .name("media_id")
+            .ok_or(ParrotError::Message(TIDAL_INVALID_QUERY))?
+            .as_str();
+
+        match media_type {
+            MediaType::Track => self.get_track_info(media_id).await,
+            MediaType::Album => self.get_track_list_info("albums", media_id).await,
+            MediaType::Playlist => self.get_track_list_info("playlists", media_id).await,
+        }
+    }
+
+    async fn get_track_info(&self, id: &str) -> Result<QueryType, ParrotError> {
+        let track: TidalTrack = self.get(&format!("tracks/{}", id)).await?;
+
+        let artist_names = Self::join_artist_names(&track.artists);
+
+        let query = Self::build_query(&artist_names, &track.title);
+        Ok(QueryType::Keywords(query))
+    }
+
+    async fn get_track_list_info(&self, kind: &str, id: &str) -> Result<QueryType, ParrotError> {
+        let list: TidalTrackList = self.get(&format!("{}/{}/tracks", kind, id)).await?;
+
+        let query_list: Vec<String> = list
+            .items
+            .iter()
+            .map(|track| {
+                let artist_names = Self::join_artist_names(&track.artists);
+                Self::build_query(&artist_names, &track.title)
+            })
+            .collect();
+
+        Ok(QueryType::KeywordList(query_list))
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T, ParrotError> {
+        let token = self
+            .token
+            .as_ref()
+            .ok_or(ParrotError::Message(TIDAL_AUTH_FAILED))?;
+
+        HTTP_CLIENT
+            .get(format!("{}/v1/{}", self.api_url, path))
+            .header("x-tidal-token", token)
+            .query(&[
+                ("countryCode", self.country_code.as_str()),
+                ("limit", TIDAL_PAGE_LIMIT),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|_| ParrotError::Message(TIDAL_INVALID_QUERY))?
+            .json()
+            .await
+            .map_err(|_| ParrotError::Message(TIDAL_INVALID_QUERY))
+    }
+
+    fn build_query(artists: &str, track_name: &str) -> String {
+        format!("{} - {}", artists, track_name)
+    }
+
+    fn join_artist_names(artists: &[TidalArtist]) -> String {
+        let artist_names: Vec<String> = artists.iter().map(|artist| artist.name.clone()).collect();
+        artist_names.join(" ")
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    io::{BufRead, BufReader, Read},
+    io::{BufRead, BufReader, Error as IoError, Read},
     process::Command,
     process::{Child, Stdio},
     time::Duration,
@@ -12,27 +12,24 @@ use songbird::input::{
     restartable::Restart,
     Codec, Container, Input, Metadata, Restartable,
 };
-use tokio::{process::Command as TokioCommand, task};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader as TokioBufReader, Lines},
+    process::{Child as TokioChild, ChildStdout, Command as TokioCommand},
+    task,
+};
 
 use crate::{
-    commands::play::{Mode, QueryType},
-    sources::ffmpeg::ffmpeg,
+    commands::play::Mode,
+    config::{FFMPEG, YTDL},
+    sources::{
+        audio_cache::AUDIO_CACHE,
+        ffmpeg::{ffmpeg, ffmpeg_file, Encoding},
+        metadata_cache::{CachedMetadata, METADATA_CACHE},
+    },
 };
 
 const NEWLINE_BYTE: u8 = 0xA;
 
-pub struct YouTube {}
-
-impl YouTube {
-    pub fn extract(query: &str) -> Option<QueryType> {
-        if query.contains("list=") {
-            Some(QueryType::PlaylistLink(query.to_string()))
-        } else {
-            Some(QueryType::VideoLink(query.to_string()))
-        }
-    }
-}
-
 pub struct YouTubeRestartable {}
 
 impl YouTubeRestartable {
@@ -40,7 +37,15 @@ impl YouTubeRestartable {
         uri: P,
         lazy: bool,
     ) -> SongbirdResult<Restartable> {
-        Restartable::new(YouTubeRestarter { uri }, lazy).await
+        Restartable::new(
+            YouTubeRestarter {
+                uri,
+                source_url: None,
+                encoding: Encoding::Pcm,
+            },
+            lazy,
+        )
+        .await
     }
 
     pub async fn ytdl_search<P: AsRef<str> + Send + Clone + Sync + 'static>(
@@ -48,10 +53,18 @@ impl YouTubeRestartable {
         lazy: bool,
     ) -> SongbirdResult<Restartable> {
         let uri = format!("ytsearch:{}", uri.as_ref());
-        Restartable::new(YouTubeRestarter { uri }, lazy).await
+        Restartable::new(
+            YouTubeRestarter {
+                uri,
+                source_url: None,
+                encoding: Encoding::Pcm,
+            },
+            lazy,
+        )
+        .await
     }
 
-    pub async fn ytdl_playlist(uri: &str, mode: Mode) -> Option<Vec<String>> {
+    pub async fn ytdl_playlist(uri: &str, mode: Mode) -> Result<YouTubePlaylist, PlaylistError> {
         let mut args = vec![uri, "--flat-playlist", "-j"];
         match mode {
             Mode::Reverse => args.push("--playlist-reverse"),
@@ -59,29 +72,87 @@ impl YouTubeRestartable {
             _ => {}
         }
 
-        let mut child = Command::new("yt-dlp")
+        let mut child = TokioCommand::new(&YTDL.path)
             .args(args)
+            .args(&YTDL.args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
             .stdout(Stdio::piped())
+            .kill_on_drop(true)
             .spawn()
-            .unwrap();
+            .map_err(PlaylistError::Spawn)?;
 
-        let Some(stdout) = &mut child.stdout else {
-            return None;
-        };
+        let stdout = child.stdout.take().ok_or(PlaylistError::Stdout)?;
+
+        Ok(YouTubePlaylist {
+            child,
+            lines: TokioBufReader::new(stdout).lines(),
+            entries: 0,
+        })
+    }
+}
+
+/// Errors that prevent the entries of a playlist from being read.
+#[derive(Debug)]
+pub enum PlaylistError {
+    /// yt-dlp couldn't be started, most likely because it isn't installed.
+    Spawn(IoError),
+    /// yt-dlp was started but its output couldn't be captured.
+    Stdout,
+    /// Reading yt-dlp's output failed midway.
+    Read(IoError),
+    /// yt-dlp exited unsuccessfully without yielding a single entry.
+    Exit(Option<i32>),
+}
+
+/// A playlist whose entries are read from yt-dlp as they're printed, so the first tracks
+/// can be queued while the rest of the playlist is still being fetched.
+pub struct YouTubePlaylist {
+    child: TokioChild,
+    lines: Lines<TokioBufReader<ChildStdout>>,
+    entries: usize,
+}
+
+impl YouTubePlaylist {
+    /// Returns the URL of the next entry, or `None` once the playlist is exhausted.
+    /// Malformed entries are skipped so a single broken video doesn't abort the playlist.
+    pub async fn next(&mut self) -> Result<Option<String>, PlaylistError> {
+        while let Some(line) = self.lines.next_line().await.map_err(PlaylistError::Read)? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: Value = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    tracing::warn!(err = ?err, "Skipping unparsable playlist entry");
+                    continue;
+                }
+            };
+
+            // flat playlists don't always resolve the page URL, but the plain URL is playable too
+            let url = ["webpage_url", "url"]
+                .iter()
+                .find_map(|key| entry.get(key).and_then(Value::as_str));
+
+            match url {
+                Some(url) => {
+                    self.entries += 1;
+                    return Ok(Some(url.to_string()));
+                }
+                None => {
+                    tracing::warn!(id = ?entry.get("id"), "Skipping playlist entry without URL")
+                }
+            }
+        }
 
-        let reader = BufReader::new(stdout);
+        let status = self.child.wait().await.map_err(PlaylistError::Read)?;
 
-        let lines = reader.lines().map_while(Result::ok).map(|line| {
-            let entry: Value = serde_json::from_str(&line).unwrap();
-            entry
-                .get("webpage_url")
-                .unwrap()
-                .as_str()
-                .unwrap()
-                .to_string()
-        });
+        if self.entries == 0 && !status.success() {
+            return Err(PlaylistError::Exit(status.code()));
+        }
 
-        Some(lines.collect())
+        Ok(None)
     }
 }
 
@@ -90,6 +161,11 @@ where
     P: AsRef<str> + Send + Sync,
 {
     uri: P,
+    /// The URL the query resolved to, searches only resolve once the track is initialized.
+    source_url: Option<String>,
+    /// Picked once the track is initialized, every restart must stick to it since songbird
+    /// keeps reading the track with the codec it was created with.
+    encoding: Encoding,
 }
 
 #[async_trait]
@@ -98,42 +174,61 @@ where
     P: AsRef<str> + Send + Clone + Sync,
 {
     async fn call_restart(&mut self, time: Option<Duration>) -> SongbirdResult<Input> {
-        let (yt, metadata) = ytdl(self.uri.as_ref()).await?;
-
-        let Some(time) = time else {
-            return ffmpeg(yt, metadata, &[]).await;
+        let ts = time.map(|time| format!("{:.3}", time.as_secs_f64()));
+        let pre_args = match &ts {
+            Some(ts) => vec!["-ss", ts.as_str()],
+            None => vec![],
         };
 
-        let ts = format!("{:.3}", time.as_secs_f64());
-        ffmpeg(yt, metadata, &["-ss", &ts]).await
+        let uri = self.source_url.as_deref().unwrap_or(self.uri.as_ref());
+        if let Some((path, metadata)) = AUDIO_CACHE.as_ref().and_then(|cache| cache.get(uri)) {
+            let encoding = self.encoding.for_stream(&metadata);
+            return ffmpeg_file(&path, metadata.to_metadata(), &pre_args, encoding).await;
+        }
+
+        let (yt, metadata) = ytdl(uri).await?;
+        METADATA_CACHE.insert(uri, &metadata);
+        self.source_url = metadata.source_url.clone();
+
+        let encoding = self.encoding.for_stream(&metadata);
+        ffmpeg(yt, metadata.to_metadata(), &pre_args, encoding).await
     }
 
     async fn lazy_init(&mut self) -> SongbirdResult<(Option<Metadata>, Codec, Container)> {
-        _ytdl_metadata(self.uri.as_ref())
-            .await
-            .map(|m| (Some(m), Codec::FloatPcm, Container::Raw))
+        let metadata = _ytdl_metadata(self.uri.as_ref()).await?;
+        self.source_url = metadata.source_url.clone();
+
+        // Opus streams can be sent as they are, the stream we get when the track starts
+        // should be the same but is transcoded to Opus if it isn't
+        if FFMPEG.passthrough && metadata.acodec.as_deref() == Some("opus") {
+            self.encoding = Encoding::Opus { transcode: false };
+        }
+
+        let (codec, container) = self.encoding.codec()?;
+        Ok((Some(metadata.to_metadata()), codec, container))
     }
 }
 
-async fn ytdl(uri: &str) -> Result<(Child, Metadata), SongbirdError> {
+async fn ytdl(uri: &str) -> Result<(Child, CachedMetadata), SongbirdError> {
     let ytdl_args = [
         "-j",            // print JSON information for video for metadata
         "-q",            // don't print progress logs (this messes with -o -)
         "--no-simulate", // ensure video is downloaded regardless of printing
         "-f",
-        "webm[abr>0]/bestaudio/best", // select best quality audio-only
+        YTDL.format.as_str(), // select best quality audio-only
         "-R",
         "infinite",        // infinite number of download retries
         "--no-playlist",   // only download the video if URL also has playlist info
         "--ignore-config", // disable all configuration files for a yt-dlp run
-        "--no-warnings", // don't print out warnings
+        "--no-warnings",   // don't print out warnings
         uri,
         "-o",
         "-", // stream data to stdout
     ];
 
-    let mut yt = Command::new("yt-dlp")
+    let mut yt = Command::new(&YTDL.path)
         .args(ytdl_args)
+        .args(&YTDL.args)
         .stdin(Stdio::null())
         .stderr(Stdio::piped())
         .stdout(Stdio::piped())
@@ -163,27 +258,32 @@ async fn ytdl(uri: &str) -> Result<(Child, Metadata), SongbirdError> {
     .await
     .map_err(|_| SongbirdError::Metadata)?;
 
-    let metadata = Metadata::from_ytdl_output(value?);
+    let metadata = CachedMetadata::from_ytdl_output(value?);
     yt.stderr = Some(returned_stderr);
 
     Ok((yt, metadata))
 }
 
-async fn _ytdl_metadata(uri: &str) -> SongbirdResult<Metadata> {
+async fn _ytdl_metadata(uri: &str) -> SongbirdResult<CachedMetadata> {
+    if let Some(cached) = METADATA_CACHE.get(uri) {
+        return Ok(cached);
+    }
+
     let ytdl_args = [
         "-j", // print JSON information for video for metadata
         "-R",
         "infinite",        // infinite number of download retries
         "--no-playlist",   // only download the video if URL also has playlist info
         "--ignore-config", // disable all configuration files for a yt-dlp run
-        "--no-warnings", // don't print out warnings
+        "--no-warnings",   // don't print out warnings
         uri,
         "-o",
         "-", // stream data to stdout
     ];
 
-    let youtube_dl_output = TokioCommand::new("yt-dlp")
+    let youtube_dl_output = TokioCommand::new(&YTDL.path)
         .args(ytdl_args)
+        .args(&YTDL.args)
         .stdin(Stdio::null())
         .output()
         .await?;
@@ -201,6 +301,8 @@ async fn _ytdl_metadata(uri: &str) -> SongbirdResult<Metadata> {
         parsed_text: std::str::from_utf8(&o_vec).unwrap_or_default().to_string(),
     })?;
 
-    let metadata = Metadata::from_ytdl_output(value);
+    let metadata = CachedMetadata::from_ytdl_output(value);
+    METADATA_CACHE.insert(uri, &metadata);
+
     Ok(metadata)
 }
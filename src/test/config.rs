@@ -0,0 +1,14 @@
+use crate::config::parse_version;
+
+#[test]
+fn test_parse_version() {
+    assert_eq!(parse_version("2023.07.06"), Some(vec![2023, 7, 6]));
+    assert_eq!(
+        parse_version("2023.07.06.185047"),
+        Some(vec![2023, 7, 6, 185047])
+    );
+    assert_eq!(parse_version("n6.0"), Some(vec![6, 0]));
+    assert_eq!(parse_version("4.4.2-0ubuntu0.22.04.1"), Some(vec![4, 4, 2]));
+    assert_eq!(parse_version("N-111234-g5ad2c4e"), None);
+    assert_eq!(parse_version(""), None);
+}
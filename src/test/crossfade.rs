@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use serenity::model::id::GuildId;
+
+use crate::{
+    guild::settings::GuildSettings,
+    handlers::crossfade::{
+        crossfade_duration, fade_volumes, is_fade_interrupted, is_fading, should_preload,
+    },
+};
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+#[test]
+fn test_crossfade_duration() {
+    let mut settings = GuildSettings::new(GuildId(1));
+    assert_eq!(crossfade_duration(None), Duration::ZERO);
+    assert_eq!(crossfade_duration(Some(&settings)), Duration::ZERO);
+
+    settings.set_crossfade(5);
+    assert_eq!(crossfade_duration(Some(&settings)), secs(5));
+
+    // pausing after every track leaves nothing to fade into
+    settings.toggle_autopause();
+    assert_eq!(crossfade_duration(Some(&settings)), Duration::ZERO);
+}
+
+#[test]
+fn test_should_preload() {
+    assert!(!should_preload(secs(11), Duration::ZERO));
+    assert!(should_preload(secs(10), Duration::ZERO));
+
+    // the next track is readied before the crossfade starts playing it
+    assert!(!should_preload(secs(16), secs(5)));
+    assert!(should_preload(secs(15), secs(5)));
+    assert!(should_preload(Duration::ZERO, secs(5)));
+}
+
+#[test]
+fn test_is_fading() {
+    assert!(!is_fading(secs(1), Duration::ZERO));
+    assert!(!is_fading(secs(6), secs(5)));
+    assert!(is_fading(secs(5), secs(5)));
+    assert!(is_fading(Duration::ZERO, secs(5)));
+}
+
+#[test]
+fn test_is_fade_interrupted() {
+    assert!(!is_fade_interrupted(true, secs(3), secs(5)));
+    assert!(!is_fade_interrupted(true, Duration::ZERO, secs(5)));
+
+    // pausing mid-fade
+    assert!(is_fade_interrupted(false, secs(3), secs(5)));
+
+    // seeking back to before the crossfade
+    assert!(is_fade_interrupted(true, secs(30), secs(5)));
+
+    // the crossfade was turned off mid-fade
+    assert!(is_fade_interrupted(true, secs(3), Duration::ZERO));
+}
+
+#[test]
+fn test_fade_volumes() {
+    assert_eq!(fade_volumes(1.0, secs(4), secs(4)), (1.0, 0.0));
+    assert_eq!(fade_volumes(1.0, secs(2), secs(4)), (0.5, 0.5));
+    assert_eq!(fade_volumes(0.5, secs(1), secs(4)), (0.125, 0.375));
+    assert_eq!(fade_volumes(0.5, Duration::ZERO, secs(4)), (0.0, 0.5));
+
+    // a track reporting more left than the crossfade doesn't go above its volume
+    assert_eq!(fade_volumes(0.8, secs(6), secs(4)), (0.8, 0.0));
+}
@@ -0,0 +1,77 @@
+use crate::{
+    guild::filter::{parse_list, ContentFilter, FilterViolation},
+    sources::metadata_cache::CachedMetadata,
+};
+
+fn track(title: &str, age_limit: u64, categories: &[&str]) -> CachedMetadata {
+    let mut metadata = CachedMetadata::default();
+    metadata.title = Some(title.to_string());
+    metadata.age_limit = Some(age_limit);
+    metadata.categories = categories.iter().map(|c| c.to_string()).collect();
+    metadata
+}
+
+#[test]
+fn test_default_filter() {
+    let filter = ContentFilter::default();
+    assert_eq!(filter.check(&track("Anything", 18, &["Gaming"])), Ok(()));
+}
+
+#[test]
+fn test_age_restricted() {
+    let filter = ContentFilter {
+        reject_age_restricted: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        filter.check(&track("Song", 18, &[])),
+        Err(FilterViolation::AgeRestricted)
+    );
+    assert_eq!(filter.check(&track("Song", 0, &[])), Ok(()));
+    assert_eq!(filter.check(&CachedMetadata::default()), Ok(()));
+}
+
+#[test]
+fn test_blocked_words() {
+    let filter = ContentFilter {
+        blocked_words: parse_list("bad, very rude\n"),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        filter.check(&track("A BAD song", 0, &[])),
+        Err(FilterViolation::BlockedWord)
+    );
+    assert_eq!(
+        filter.check(&track("Something (Very  Rude remix)", 0, &[])),
+        Err(FilterViolation::BlockedWord)
+    );
+
+    // only whole words count
+    assert_eq!(filter.check(&track("Badminton highlights", 0, &[])), Ok(()));
+    assert_eq!(filter.check(&track("Very polite, rude", 0, &[])), Ok(()));
+}
+
+#[test]
+fn test_allowed_categories() {
+    let filter = ContentFilter {
+        allowed_categories: parse_list("music"),
+        ..Default::default()
+    };
+
+    assert_eq!(filter.check(&track("Song", 0, &["Music"])), Ok(()));
+    assert_eq!(
+        filter.check(&track("Let's play", 0, &["Gaming"])),
+        Err(FilterViolation::Category)
+    );
+
+    // sources without categories can't be told apart, so they're let through
+    assert_eq!(filter.check(&track("Song", 0, &[])), Ok(()));
+}
+
+#[test]
+fn test_parse_list() {
+    assert_eq!(parse_list(" a, b\n\nc d ,"), ["a", "b", "c d"]);
+    assert!(parse_list("  ").is_empty());
+}
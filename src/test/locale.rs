@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::messaging::{
+    locale::{available_locales, parse_catalog, resolve_locale, tr, translate, with_locale},
+    messages::{
+        CATALOG, CLEARED, DEEZER_PLAYLIST_FAILED, LANGUAGE_NAME, PLAYLIST_FAILED, REMOVE_CANCEL,
+        SETTINGS_IMPORT_CANCEL,
+    },
+};
+
+fn catalog() -> HashMap<String, String> {
+    CATALOG
+        .iter()
+        .map(|message| (message.name.to_string(), message.text.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_english_catalog() {
+    // the English translation is what translators start from, so it must not fall behind
+    let english = parse_catalog(include_str!("../../locales/en-US.json")).unwrap();
+    assert_eq!(english, catalog());
+}
+
+#[test]
+fn test_template_catalog() {
+    let template = parse_catalog(include_str!("../../locales/template.json")).unwrap();
+
+    let mut names: Vec<_> = template.keys().collect();
+    let mut expected: Vec<_> = CATALOG.iter().map(|message| message.name).collect();
+    names.sort_unstable();
+    expected.sort_unstable();
+
+    assert_eq!(names, expected);
+    assert!(template.values().all(String::is_empty));
+}
+
+#[test]
+fn test_resolve_locale() {
+    assert_eq!(available_locales(), ["en-US"]);
+    assert_eq!(resolve_locale("en-US"), Some("en-US"));
+    assert_eq!(resolve_locale("en-us"), Some("en-US"));
+    assert_eq!(resolve_locale("en-GB"), Some("en-US"));
+    assert_eq!(resolve_locale("pt-BR"), None);
+    assert_eq!(resolve_locale(""), None);
+}
+
+#[test]
+fn test_translate() {
+    assert_eq!(translate("en-US", LANGUAGE_NAME), "English");
+    assert_eq!(translate("pt-BR", CLEARED), CLEARED.text);
+}
+
+#[test]
+fn test_messages_sharing_text() {
+    // messages with the same English text are still translated separately
+    assert_eq!(PLAYLIST_FAILED.text, DEEZER_PLAYLIST_FAILED.text);
+    assert_ne!(PLAYLIST_FAILED, DEEZER_PLAYLIST_FAILED);
+    assert_eq!(REMOVE_CANCEL.text, SETTINGS_IMPORT_CANCEL.text);
+    assert_ne!(REMOVE_CANCEL, SETTINGS_IMPORT_CANCEL);
+
+    let mut names: Vec<_> = CATALOG.iter().map(|message| message.name).collect();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), CATALOG.len());
+}
+
+#[tokio::test]
+async fn test_tr() {
+    assert_eq!(tr(CLEARED), CLEARED.text);
+    assert_eq!(
+        with_locale(Some("en-US"), async { tr(CLEARED) }).await,
+        CLEARED.text
+    );
+    assert_eq!(
+        with_locale(None, async { tr(LANGUAGE_NAME) }).await,
+        "English"
+    );
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use serenity::async_trait;
+use songbird::input::Metadata;
+
+use crate::{
+    errors::ParrotError,
+    lyrics::{
+        captions::parse_vtt, find_lyrics, paginate_lyrics, Lyrics, LyricsProvider, LyricsQuery,
+    },
+};
+
+/// Answers every lookup the same way, no network involved.
+struct StubProvider(&'static str, Result<Option<&'static str>, ()>);
+
+#[async_trait]
+impl LyricsProvider for StubProvider {
+    fn name(&self) -> &'static str {
+        self.0
+    }
+
+    async fn lyrics(&self, _query: &LyricsQuery) -> Result<Option<String>, ParrotError> {
+        self.1
+            .map(|lyrics| lyrics.map(String::from))
+            .map_err(|_| ParrotError::NothingPlaying)
+    }
+}
+
+#[tokio::test]
+async fn test_find_lyrics() {
+    let query = LyricsQuery {
+        title: String::from("Song"),
+        ..Default::default()
+    };
+
+    let providers: Vec<Box<dyn LyricsProvider>> = vec![
+        Box::new(StubProvider("broken", Err(()))),
+        Box::new(StubProvider("empty", Ok(None))),
+        Box::new(StubProvider("blank", Ok(Some("  \n")))),
+        Box::new(StubProvider("stub", Ok(Some("la la la")))),
+        Box::new(StubProvider("unused", Ok(Some("never asked")))),
+    ];
+
+    assert_eq!(
+        find_lyrics(&providers, &query).await,
+        Some(Lyrics {
+            text: String::from("la la la"),
+            provider: "stub",
+        })
+    );
+
+    assert_eq!(find_lyrics(&providers[..3], &query).await, None);
+}
+
+#[test]
+fn test_lyrics_query_from_metadata() {
+    let metadata = Metadata {
+        title: Some(String::from("Daft Punk - One More Time (Official Video)")),
+        channel: Some(String::from("Daft Punk")),
+        duration: Some(Duration::from_secs(320)),
+        ..Default::default()
+    };
+
+    let query = LyricsQuery::from_metadata(&metadata).unwrap();
+    assert_eq!(query.title, "One More Time");
+    assert_eq!(query.artist.as_deref(), Some("Daft Punk"));
+    assert_eq!(query.duration, Some(Duration::from_secs(320)));
+
+    let metadata = Metadata {
+        title: Some(String::from("Around the World [Remastered]")),
+        channel: Some(String::from("Daft Punk - Topic")),
+        ..Default::default()
+    };
+
+    let query = LyricsQuery::from_metadata(&metadata).unwrap();
+    assert_eq!(query.title, "Around the World");
+    assert_eq!(query.artist.as_deref(), Some("Daft Punk"));
+
+    assert_eq!(LyricsQuery::from_metadata(&Metadata::default()), None);
+}
+
+#[test]
+fn test_parse_vtt() {
+    let vtt = "WEBVTT\nKind: captions\nLanguage: en\n\nNOTE generated\nby youtube\n\n1\n00:00:01.000 --> 00:00:03.000\n<c.colorE5E5E5>one more</c><00:00:02.000><c> time</c>\n\n00:00:03.000 --> 00:00:04.000\none more time\n\n00:00:04.000 --> 00:00:06.000\nwe&amp;re gonna celebrate\n";
+
+    assert_eq!(parse_vtt(vtt), "one more time\nwe&re gonna celebrate");
+}
+
+#[test]
+fn test_paginate_lyrics() {
+    assert_eq!(paginate_lyrics("", 10), vec![String::new()]);
+    assert_eq!(
+        paginate_lyrics("one\ntwo\nthree\nfour", 10),
+        vec!["one\ntwo", "three\nfour"]
+    );
+    assert_eq!(paginate_lyrics("abcdefghijkl", 5), vec!["abcde"]);
+}
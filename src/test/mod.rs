@@ -1,2 +1,13 @@
+pub mod config;
+pub mod crossfade;
 pub mod errors;
+pub mod filter;
+pub mod locale;
+pub mod lyrics;
+pub mod queue;
+pub mod rules;
+pub mod seek;
+pub mod settings;
+pub mod sources;
+pub mod theme;
 pub mod utils;
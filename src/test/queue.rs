@@ -0,0 +1,141 @@
+use serenity::model::id::UserId;
+
+use crate::commands::{
+    autocomplete::suggest_tracks,
+    dedupe::find_duplicates,
+    queue_file::{read_queue, write_queue, QueueEntry, QueueFormat},
+    remove::RemoveFilter,
+};
+
+fn entries() -> Vec<QueueEntry> {
+    vec![
+        QueueEntry {
+            url: String::from("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+            title: Some(String::from("Never Gonna Give You Up")),
+            duration: Some(212),
+            requested_by: Some(UserId(80351110224678912)),
+        },
+        QueueEntry {
+            url: String::from("https://www.youtube.com/watch?v=jfKfPfyJRdk"),
+            title: Some(String::from("lofi hip hop radio, beats to relax/study to")),
+            duration: None,
+            requested_by: None,
+        },
+    ]
+}
+
+#[test]
+fn test_queue_format_from_filename() {
+    assert_eq!(
+        QueueFormat::from_filename("queue.json"),
+        Some(QueueFormat::Json)
+    );
+    assert_eq!(
+        QueueFormat::from_filename("mix.M3U8"),
+        Some(QueueFormat::M3u)
+    );
+    assert_eq!(QueueFormat::from_filename("queue.txt"), None);
+    assert_eq!(QueueFormat::from_filename("queue"), None);
+}
+
+#[test]
+fn test_queue_json_round_trip() {
+    let json = write_queue(&entries(), QueueFormat::Json).unwrap();
+    assert_eq!(read_queue(&json, QueueFormat::Json).unwrap(), entries());
+
+    // only the url is needed to queue a track
+    let minimal = r#"[{"url":"https://youtu.be/dQw4w9WgXcQ"}]"#;
+    assert_eq!(
+        read_queue(minimal, QueueFormat::Json).unwrap(),
+        vec![QueueEntry {
+            url: String::from("https://youtu.be/dQw4w9WgXcQ"),
+            ..Default::default()
+        }]
+    );
+
+    assert!(read_queue("#EXTM3U", QueueFormat::Json).is_err());
+}
+
+#[test]
+fn test_queue_m3u_round_trip() {
+    let m3u = write_queue(&entries(), QueueFormat::M3u).unwrap();
+    assert!(m3u.starts_with(
+        "#EXTM3U\n#EXTINF:212 requested-by=\"80351110224678912\",Never Gonna Give You Up\n"
+    ));
+    assert_eq!(read_queue(&m3u, QueueFormat::M3u).unwrap(), entries());
+
+    // plain playlists from other players have no extended info
+    let plain = "https://youtu.be/dQw4w9WgXcQ\n\n# a comment\nhttps://youtu.be/jfKfPfyJRdk\n";
+    let urls: Vec<String> = read_queue(plain, QueueFormat::M3u)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.url)
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            "https://youtu.be/dQw4w9WgXcQ",
+            "https://youtu.be/jfKfPfyJRdk"
+        ]
+    );
+}
+
+#[test]
+fn test_find_duplicates() {
+    let urls = [
+        Some(String::from("https://youtu.be/a")),
+        Some(String::from("https://youtu.be/b")),
+        None,
+        Some(String::from("https://youtu.be/a")),
+        None,
+        Some(String::from("https://youtu.be/b")),
+        Some(String::from("https://youtu.be/a")),
+    ];
+
+    assert_eq!(find_duplicates(&urls), vec![3, 5, 6]);
+    assert!(find_duplicates(&[]).is_empty());
+}
+
+#[test]
+fn test_remove_filter() {
+    let user = RemoveFilter::User(UserId(1));
+    assert!(user.matches(Some(UserId(1)), Some("Song")));
+    assert!(!user.matches(Some(UserId(2)), Some("Song")));
+    assert!(!user.matches(None, Some("Song")));
+
+    let text = RemoveFilter::Match(String::from("rick"));
+    assert!(text.matches(None, Some("Rick Astley - Never Gonna Give You Up")));
+    assert!(!text.matches(Some(UserId(1)), Some("Daft Punk - One More Time")));
+    assert!(!text.matches(Some(UserId(1)), None));
+}
+
+#[test]
+fn test_suggest_tracks() {
+    let titles = [
+        Some(String::from("Playing Now")),
+        Some(String::from("Daft Punk - One More Time")),
+        None,
+        Some(String::from("Daft Punk - Aerodynamic")),
+    ];
+
+    // the track that's playing is never suggested
+    assert_eq!(
+        suggest_tracks(&titles, ""),
+        vec![
+            (1, String::from("1. Daft Punk - One More Time")),
+            (2, String::from("2. ")),
+            (3, String::from("3. Daft Punk - Aerodynamic")),
+        ]
+    );
+    assert_eq!(
+        suggest_tracks(&titles, "aero"),
+        vec![(3, String::from("3. Daft Punk - Aerodynamic"))]
+    );
+    assert_eq!(suggest_tracks(&titles, "2"), vec![(2, String::from("2. "))]);
+    assert!(suggest_tracks(&titles, "playing").is_empty());
+
+    let titles = vec![Some("x".repeat(200)); 30];
+    let choices = suggest_tracks(&titles, "");
+    assert_eq!(choices.len(), 25);
+    assert!(choices.iter().all(|(_, name)| name.chars().count() <= 100));
+}
@@ -0,0 +1,98 @@
+use url::Url;
+
+use crate::guild::rules::{is_source_allowed, parse_rules, SourceRule};
+
+fn allowed(rules: &str, url: &str) -> bool {
+    is_source_allowed(&parse_rules(rules).unwrap(), &Url::parse(url).unwrap())
+}
+
+#[test]
+fn test_host_rules() {
+    // a host covers its subdomains, but not hosts that merely end the same way
+    assert!(allowed(
+        "allow youtube.com",
+        "https://youtube.com/watch?v=1"
+    ));
+    assert!(allowed(
+        "allow youtube.com",
+        "https://www.YouTube.com/watch?v=1"
+    ));
+    assert!(!allowed(
+        "allow youtube.com",
+        "https://notyoutube.com/watch?v=1"
+    ));
+    assert!(!allowed(
+        "allow youtube.com",
+        "https://soundcloud.com/artist"
+    ));
+
+    // wildcards only cover what they stand for
+    assert!(allowed(
+        "allow *.bandcamp.com",
+        "https://artist.bandcamp.com/album/a"
+    ));
+    assert!(!allowed(
+        "allow *.bandcamp.com",
+        "https://bandcamp.com/discover"
+    ));
+}
+
+#[test]
+fn test_path_rules() {
+    let rules = "allow youtube.com/playlist?list=PL1*\nallow youtube.com/@parrot";
+
+    assert!(allowed(
+        rules,
+        "https://www.youtube.com/playlist?list=PL123"
+    ));
+    assert!(allowed(rules, "https://www.youtube.com/@parrot/videos"));
+    assert!(!allowed(rules, "https://www.youtube.com/playlist?list=PL2"));
+    assert!(!allowed(rules, "https://www.youtube.com/watch?v=1"));
+}
+
+#[test]
+fn test_regex_rules() {
+    let rules = r"ban /^https://[^/]+/watch\?v=bad/";
+
+    assert!(!allowed(rules, "https://www.youtube.com/watch?v=bad"));
+    assert!(allowed(rules, "https://www.youtube.com/watch?v=good"));
+}
+
+#[test]
+fn test_rule_order() {
+    // the first rule that matches decides
+    let rules = "ban youtube.com/@spam\nallow youtube.com";
+    assert!(!allowed(rules, "https://www.youtube.com/@spam"));
+    assert!(allowed(rules, "https://www.youtube.com/@parrot"));
+
+    let rules = "allow youtube.com\nban youtube.com/@spam";
+    assert!(allowed(rules, "https://www.youtube.com/@spam"));
+
+    // nothing else is allowed while there's an allow rule, everything is with only bans
+    assert!(!allowed(
+        "allow youtube.com\nban soundcloud.com",
+        "https://bandcamp.com"
+    ));
+    assert!(allowed("ban soundcloud.com", "https://bandcamp.com"));
+}
+
+#[test]
+fn test_parse_rules() {
+    let rules = parse_rules("allow youtube.com\n\n  BAN  *.bandcamp.com  ").unwrap();
+    assert_eq!(
+        rules,
+        [
+            SourceRule::allow("youtube.com").unwrap(),
+            SourceRule::ban("*.bandcamp.com").unwrap(),
+        ]
+    );
+
+    // rules are shown the way they were written
+    assert_eq!(rules[1].to_string(), "ban *.bandcamp.com");
+
+    let invalid = parse_rules("allow youtube.com\ndeny a.com\nban\nban /(/\nallow a b.com");
+    assert_eq!(
+        invalid.unwrap_err(),
+        ["deny a.com", "ban", "ban /(/", "allow a b.com"]
+    );
+}
@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use crate::{
+    commands::seek::{next_chapter, previous_chapter, SeekTarget},
+    sources::metadata_cache::Chapter,
+    utils::parse_timestamp,
+};
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+fn chapters() -> Vec<Chapter> {
+    [
+        ("Intro", 0.0, 60.0),
+        ("Verse", 60.0, 150.0),
+        ("Outro", 150.0, 200.0),
+    ]
+    .iter()
+    .map(|(title, start_time, end_time)| Chapter {
+        title: title.to_string(),
+        start_time: *start_time,
+        end_time: *end_time,
+    })
+    .collect()
+}
+
+#[test]
+fn test_parse_timestamp() {
+    assert_eq!(parse_timestamp("45"), Some(secs(45)));
+    assert_eq!(parse_timestamp("90"), Some(secs(90)));
+    assert_eq!(parse_timestamp("1:30"), Some(secs(90)));
+    assert_eq!(parse_timestamp("01:02:03"), Some(secs(3723)));
+    assert_eq!(parse_timestamp("1:60"), None);
+    assert_eq!(parse_timestamp("1:2:3:4"), None);
+    assert_eq!(parse_timestamp("abc"), None);
+    assert_eq!(parse_timestamp(""), None);
+    assert_eq!(parse_timestamp("18446744073709551615:00"), None);
+    assert_eq!(parse_timestamp("18446744073709551615:00:00"), None);
+}
+
+#[test]
+fn test_seek_target() {
+    assert_eq!(
+        SeekTarget::parse("1:00"),
+        Some(SeekTarget::Absolute(secs(60)))
+    );
+    assert_eq!(
+        SeekTarget::parse("+30"),
+        Some(SeekTarget::Forward(secs(30)))
+    );
+    assert_eq!(
+        SeekTarget::parse("-1:00"),
+        Some(SeekTarget::Backward(secs(60)))
+    );
+    assert_eq!(SeekTarget::parse("+-30"), None);
+
+    let duration = Some(secs(200));
+    assert_eq!(
+        SeekTarget::Forward(secs(30)).resolve(secs(100), duration),
+        secs(130)
+    );
+    assert_eq!(
+        SeekTarget::Forward(secs(300)).resolve(secs(100), duration),
+        secs(200)
+    );
+    assert_eq!(
+        SeekTarget::Backward(secs(300)).resolve(secs(100), duration),
+        secs(0)
+    );
+    assert_eq!(
+        SeekTarget::Forward(secs(300)).resolve(secs(100), None),
+        secs(400)
+    );
+
+    let target = SeekTarget::parse("+18446744073709551615").unwrap();
+    assert_eq!(target, SeekTarget::Forward(secs(u64::MAX)));
+    assert_eq!(target.resolve(secs(100), duration), secs(200));
+    assert_eq!(target.resolve(secs(100), None), Duration::MAX);
+}
+
+#[test]
+fn test_chapter_navigation() {
+    let chapters = chapters();
+
+    let title = |chapter: Option<Chapter>| chapter.map(|chapter| chapter.title);
+
+    assert_eq!(
+        title(next_chapter(&chapters, secs(10))),
+        Some("Verse".into())
+    );
+    assert_eq!(title(next_chapter(&chapters, secs(160))), None);
+
+    assert_eq!(
+        title(previous_chapter(&chapters, secs(100))),
+        Some("Verse".into())
+    );
+    assert_eq!(
+        title(previous_chapter(&chapters, secs(61))),
+        Some("Intro".into())
+    );
+    assert_eq!(
+        title(previous_chapter(&chapters, secs(1))),
+        Some("Intro".into())
+    );
+    assert!(previous_chapter(&[], secs(1)).is_none());
+}
@@ -0,0 +1,312 @@
+use std::{env, fs};
+
+use serde_json::json;
+use serenity::model::id::{ChannelId, GuildId};
+
+use crate::commands::{create_settings_embed, diff_settings, parse_settings_file};
+use crate::guild::{
+    migrations::{migrate, CURRENT_SETTINGS_VERSION},
+    rules::{parse_rules, SourceRule},
+    settings::{normalize_domain, GuildSettings},
+    store::{JsonStore, SettingsStore, SqliteStore},
+    visibility::ResponseKind,
+};
+
+fn assert_round_trip(store: &dyn SettingsStore) {
+    let guild_id = GuildId(1234);
+    assert!(store.load(guild_id).unwrap().is_none());
+
+    let mut settings = GuildSettings::new(guild_id);
+    settings.toggle_autopause();
+    settings.set_crossfade(5);
+    store.save(&settings).unwrap();
+
+    // saving again replaces what was there
+    settings.set_default_volume(0.5);
+    store.save(&settings).unwrap();
+
+    let loaded = store.load(guild_id).unwrap().unwrap();
+    assert_eq!(loaded.guild_id, guild_id);
+    assert!(loaded.autopause);
+    assert_eq!(loaded.crossfade, 5);
+    assert_eq!(loaded.default_volume, 0.5);
+    assert_eq!(loaded.source_rules, settings.source_rules);
+
+    assert!(store.load(GuildId(5678)).unwrap().is_none());
+}
+
+#[test]
+fn test_json_store() {
+    let path = env::temp_dir().join(format!("parrot-settings-{}", std::process::id()));
+    let store = JsonStore::new(&path);
+
+    assert_round_trip(&store);
+
+    // nothing is left behind from the atomic writes
+    let files: Vec<_> = fs::read_dir(&path)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["1234.json"]);
+
+    fs::remove_dir_all(path).ok();
+}
+
+#[test]
+fn test_sqlite_store() {
+    let store = SqliteStore::open_in_memory().unwrap();
+    assert_round_trip(&store);
+}
+
+#[test]
+fn test_migrate_unversioned_settings() {
+    // as saved before settings had a version, crossfade or duplicate detection
+    let legacy = json!({
+        "guild_id": "1234",
+        "autopause": true,
+        "allowed_domains": [],
+        "banned_domains": ["https://SoundCloud.com/", " soundcloud.com", ""],
+        "default_volume": 0.4
+    });
+
+    let settings = migrate(legacy).unwrap();
+    assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+    assert_eq!(settings.guild_id, GuildId(1234));
+    assert!(settings.autopause);
+    assert_eq!(settings.default_volume, 0.4);
+    assert_eq!(settings.crossfade, 0);
+    assert!(!settings.no_duplicates);
+    assert_eq!(
+        settings.source_rules,
+        [SourceRule::ban("soundcloud.com").unwrap()]
+    );
+}
+
+#[test]
+fn test_migrate_domains_to_rules() {
+    // allowed domains only ever applied while nothing was banned
+    let legacy = json!({
+        "version": 1,
+        "allowed_domains": ["youtube.com"],
+        "banned_domains": ["soundcloud.com"]
+    });
+    assert_eq!(
+        migrate(legacy).unwrap().source_rules,
+        [SourceRule::ban("soundcloud.com").unwrap()]
+    );
+
+    let legacy = json!({
+        "version": 1,
+        "allowed_domains": ["youtube.com", "bandcamp.com"],
+        "banned_domains": []
+    });
+    assert_eq!(
+        migrate(legacy).unwrap().source_rules,
+        [
+            SourceRule::allow("youtube.com").unwrap(),
+            SourceRule::allow("bandcamp.com").unwrap()
+        ]
+    );
+
+    // with neither, the defaults apply
+    let legacy = json!({ "version": 1, "allowed_domains": [], "banned_domains": [] });
+    assert_eq!(
+        migrate(legacy).unwrap().source_rules,
+        GuildSettings::default().source_rules
+    );
+}
+
+#[test]
+fn test_migrate_current_settings() {
+    let mut settings = GuildSettings::new(GuildId(1));
+    settings.set_crossfade(3);
+
+    let migrated = migrate(serde_json::to_value(&settings).unwrap()).unwrap();
+    assert_eq!(migrated.version, CURRENT_SETTINGS_VERSION);
+    assert_eq!(migrated.crossfade, 3);
+    assert_eq!(migrated.source_rules, settings.source_rules);
+
+    // settings from a newer version still load, minus what this one doesn't know about
+    let future =
+        json!({ "version": CURRENT_SETTINGS_VERSION + 1, "autopause": true, "unknown": 1 });
+    assert!(migrate(future).unwrap().autopause);
+}
+
+#[test]
+fn test_normalize_domain() {
+    assert_eq!(normalize_domain("youtube.com"), "youtube.com");
+    assert_eq!(
+        normalize_domain(" https://www.YouTube.com/watch "),
+        "www.youtube.com"
+    );
+    assert_eq!(normalize_domain("soundcloud.com/artist"), "soundcloud.com");
+    assert_eq!(normalize_domain("  "), "");
+}
+
+#[test]
+fn test_settings_embed() {
+    let mut settings = GuildSettings::new(GuildId(1));
+    settings.toggle_autopause();
+    settings.set_crossfade(4);
+    settings.set_default_volume(0.3);
+    settings.set_source_rules(parse_rules("ban b.com\nallow *.a.com").unwrap());
+
+    let embed = create_settings_embed(&settings);
+    let fields: Vec<(String, String)> = embed.0["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| {
+            let name = field["name"].as_str().unwrap().to_string();
+            let value = field["value"].as_str().unwrap().to_string();
+            (name, value)
+        })
+        .collect();
+
+    let value = |name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap()
+    };
+
+    assert_eq!(value("Autopause"), ">>> On");
+    assert_eq!(value("Default volume"), ">>> 30%");
+    assert_eq!(value("Crossfade"), ">>> 4s");
+    assert_eq!(value("Duplicates"), ">>> Allowed");
+    assert_eq!(value("Sources"), ">>> `ban b.com`\n`allow *.a.com`");
+    assert_eq!(value("Command channels"), ">>> Any");
+}
+
+#[test]
+fn test_settings_embed_long_lists() {
+    let mut settings = GuildSettings::new(GuildId(1));
+    settings.content_filter.blocked_words = (0..500).map(|i| format!("word{}", i)).collect();
+
+    let embed = create_settings_embed(&settings);
+    let fields = embed.0["fields"].as_array().unwrap();
+    let words = fields
+        .iter()
+        .find(|field| field["name"] == "Blocked words")
+        .and_then(|field| field["value"].as_str())
+        .unwrap();
+
+    assert_eq!(words.chars().count(), 1024);
+    assert!(words.ends_with('…'));
+}
+
+#[test]
+fn test_channels() {
+    let mut settings = GuildSettings::new(GuildId(1));
+
+    // no channels allows every channel
+    assert!(settings.is_command_channel(ChannelId(10)));
+    assert!(settings.is_voice_channel(ChannelId(20)));
+
+    settings.add_command_channel(ChannelId(10));
+    settings.add_command_channel(ChannelId(10));
+    settings.add_voice_channel(ChannelId(20));
+    assert_eq!(settings.command_channels, [ChannelId(10)]);
+    assert!(settings.is_command_channel(ChannelId(10)));
+    assert!(!settings.is_command_channel(ChannelId(11)));
+    assert!(settings.is_voice_channel(ChannelId(20)));
+    assert!(!settings.is_voice_channel(ChannelId(10)));
+
+    assert!(settings.remove_channel(ChannelId(20)));
+    assert!(!settings.remove_channel(ChannelId(20)));
+    assert!(settings.is_voice_channel(ChannelId(21)));
+
+    settings.clear_channels();
+    assert!(settings.is_command_channel(ChannelId(11)));
+}
+
+#[test]
+fn test_response_visibility() {
+    let mut settings = GuildSettings::new(GuildId(1));
+
+    // every kind starts out private
+    assert!(ResponseKind::ALL
+        .iter()
+        .all(|kind| !settings.is_response_public(*kind)));
+
+    settings.set_response_public(ResponseKind::Version, true);
+    settings.set_response_public(ResponseKind::Version, true);
+    assert_eq!(settings.public_responses, [ResponseKind::Version]);
+    assert!(settings.is_response_public(ResponseKind::Version));
+    assert!(!settings.is_response_public(ResponseKind::Errors));
+
+    settings.set_response_public(ResponseKind::Version, false);
+    assert!(settings.public_responses.is_empty());
+
+    assert_eq!(ResponseKind::from_id("errors"), Some(ResponseKind::Errors));
+    assert_eq!(ResponseKind::from_id("queue"), None);
+}
+
+#[test]
+fn test_parse_settings_file() {
+    let mut exported = GuildSettings::new(GuildId(1));
+    exported.toggle_autopause();
+    exported.set_crossfade(6);
+    let contents = serde_json::to_string(&exported).unwrap();
+
+    // the settings take the guild they're imported into
+    let imported = parse_settings_file(&contents, GuildId(2)).unwrap();
+    assert_eq!(imported.guild_id, GuildId(2));
+    assert!(imported.autopause);
+    assert_eq!(imported.crossfade, 6);
+
+    // older exports are migrated like saved settings are
+    let imported = parse_settings_file(
+        r#"{ "allowed_domains": [], "banned_domains": ["https://A.com/"] }"#,
+        GuildId(2),
+    );
+    assert_eq!(
+        imported.unwrap().source_rules,
+        [SourceRule::ban("a.com").unwrap()]
+    );
+
+    let invalid = json!({ "source_rules": ["allow *.a.com", "deny b.com"] }).to_string();
+    assert!(parse_settings_file(&invalid, GuildId(2)).is_err());
+
+    assert!(parse_settings_file("not json", GuildId(2)).is_err());
+    assert!(parse_settings_file("[]", GuildId(2)).is_err());
+    assert!(parse_settings_file(r#"{ "unknown": 1 }"#, GuildId(2)).is_err());
+    assert!(parse_settings_file(r#"{ "autopause": "yes" }"#, GuildId(2)).is_err());
+    assert!(parse_settings_file(r#"{ "default_volume": 5.0 }"#, GuildId(2)).is_err());
+    assert!(parse_settings_file(r#"{ "crossfade": 60 }"#, GuildId(2)).is_err());
+    assert!(parse_settings_file(r#"{ "language": "xx-XX" }"#, GuildId(2)).is_err());
+    assert!(parse_settings_file(r#"{ "public_responses": ["queue"] }"#, GuildId(2)).is_err());
+    assert!(
+        parse_settings_file(r#"{ "theme": { "accent_color": 16777216 } }"#, GuildId(2)).is_err()
+    );
+
+    let imported = parse_settings_file(r#"{ "language": "en-US" }"#, GuildId(2));
+    assert_eq!(imported.unwrap().language.as_deref(), Some("en-US"));
+
+    let future = json!({ "version": CURRENT_SETTINGS_VERSION + 1 }).to_string();
+    assert!(parse_settings_file(&future, GuildId(2)).is_err());
+}
+
+#[test]
+fn test_diff_settings() {
+    let current = GuildSettings::new(GuildId(1));
+    assert!(diff_settings(&current, &GuildSettings::new(GuildId(2))).is_empty());
+
+    let mut imported = GuildSettings::new(GuildId(1));
+    imported.set_crossfade(4);
+    imported.set_no_duplicates(true);
+
+    let changes = diff_settings(&current, &imported);
+    assert_eq!(
+        changes,
+        [
+            ("Crossfade", String::from("Off"), String::from("4s")),
+            (
+                "Duplicates",
+                String::from("Allowed"),
+                String::from("Rejected")
+            ),
+        ]
+    );
+}
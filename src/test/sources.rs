@@ -0,0 +1,399 @@
+use std::{
+    env, fs,
+    io::Read,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server,
+};
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    commands::play::QueryType,
+    errors::ParrotError,
+    messaging::messages::{
+        APPLE_MUSIC_AUTH_FAILED, DEEZER_INVALID_QUERY, DEEZER_PLAYLIST_FAILED, TIDAL_AUTH_FAILED,
+    },
+    sources::{
+        apple_music::AppleMusic,
+        audio_cache::{self, AudioCache},
+        deezer::Deezer,
+        metadata_cache::{cache_key, CachedMetadata, MetadataCache},
+        ogg::OggOpusReader,
+        provider::Provider,
+        tidal::Tidal,
+    },
+    utils::get_footer_info,
+};
+
+fn is_playlist(query: &str) -> bool {
+    matches!(Provider::extract(query), Some(QueryType::PlaylistLink(_)))
+}
+
+#[test]
+fn test_provider_from_url() {
+    let cases = [
+        (
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            Provider::YouTube,
+        ),
+        ("https://youtu.be/dQw4w9WgXcQ", Provider::YouTube),
+        ("https://m.soundcloud.com/parrot/song", Provider::SoundCloud),
+        (
+            "https://parrot.bandcamp.com/album/songs",
+            Provider::Bandcamp,
+        ),
+        ("https://vimeo.com/76979871", Provider::Vimeo),
+        ("https://www.twitch.tv/videos/1234", Provider::Twitch),
+        ("https://example.com/song.mp3", Provider::Generic),
+        ("https://notbandcamp.com/album/songs", Provider::Generic),
+    ];
+
+    for (url, provider) in cases {
+        assert_eq!(
+            Provider::from_url(&Url::parse(url).unwrap()),
+            provider,
+            "{url}"
+        );
+    }
+}
+
+#[test]
+fn test_provider_playlists() {
+    assert!(is_playlist("https://www.youtube.com/playlist?list=PL123"));
+    assert!(is_playlist(
+        "https://www.youtube.com/watch?v=abc&list=PL123"
+    ));
+    assert!(is_playlist("https://soundcloud.com/parrot/sets/birds"));
+    assert!(is_playlist("https://soundcloud.com/parrot"));
+    assert!(is_playlist("https://parrot.bandcamp.com/album/songs"));
+    assert!(is_playlist("https://parrot.bandcamp.com/"));
+    assert!(is_playlist("https://vimeo.com/showcase/1234"));
+    assert!(is_playlist("https://vimeo.com/channels/staffpicks"));
+    assert!(is_playlist("https://www.twitch.tv/parrot/videos"));
+
+    assert!(!is_playlist("https://www.youtube.com/watch?v=abc"));
+    assert!(!is_playlist("https://soundcloud.com/parrot/song"));
+    assert!(!is_playlist("https://on.soundcloud.com/AbCdE"));
+    assert!(!is_playlist("https://parrot.bandcamp.com/track/song"));
+    assert!(!is_playlist("https://vimeo.com/76979871"));
+    assert!(!is_playlist(
+        "https://vimeo.com/channels/staffpicks/76979871"
+    ));
+    assert!(!is_playlist("https://www.twitch.tv/videos/1234"));
+    assert!(!is_playlist("https://example.com/list=song.mp3"));
+}
+
+#[test]
+fn test_get_footer_info() {
+    let (text, icon) = get_footer_info("https://music.youtube.com/watch?v=abc");
+    assert_eq!(text, "Streaming via YouTube");
+    assert_eq!(
+        icon,
+        "https://www.google.com/s2/favicons?domain=youtube.com"
+    );
+
+    let (text, icon) = get_footer_info("https://www.example.com/song.mp3");
+    assert_eq!(text, "Streaming via example.com");
+    assert_eq!(
+        icon,
+        "https://www.google.com/s2/favicons?domain=example.com"
+    );
+}
+
+/// Serves canned JSON bodies for requests whose path and query start with the given prefixes.
+async fn mock_server(routes: &'static [(&'static str, &'static str)]) -> String {
+    let make_service = make_service_fn(move |_| async move {
+        Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| async move {
+            let path = req.uri().path_and_query().unwrap().as_str().to_string();
+
+            let response = match routes.iter().find(|(route, _)| path.starts_with(route)) {
+                Some((_, body)) => Response::new(Body::from(*body)),
+                None => Response::builder().status(404).body(Body::empty()).unwrap(),
+            };
+
+            Ok::<_, hyper::Error>(response)
+        }))
+    });
+
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+
+    url
+}
+
+#[tokio::test]
+async fn test_deezer_extract() {
+    let url = mock_server(&[
+        ("/track/3135556", r#"{"title":"Harder, Better, Faster, Stronger","artist":{"name":"Daft Punk"}}"#),
+        ("/album/302127", r#"{"tracks":{"data":[{"title":"One More Time","artist":{"name":"Daft Punk"}},{"title":"Aerodynamic","artist":{"name":"Daft Punk"}}]}}"#),
+        ("/playlist/404", r#"{"error":{"type":"DataException","message":"no data","code":800}}"#),
+    ])
+    .await;
+
+    let deezer = Deezer::new(url);
+
+    let query = deezer
+        .extract("https://www.deezer.com/en/track/3135556")
+        .await;
+    assert!(
+        matches!(query, Ok(QueryType::Keywords(q)) if q == "Daft Punk - Harder, Better, Faster, Stronger")
+    );
+
+    let query = deezer.extract("https://www.deezer.com/album/302127").await;
+    assert!(
+        matches!(query, Ok(QueryType::KeywordList(list)) if list == ["Daft Punk - One More Time", "Daft Punk - Aerodynamic"])
+    );
+
+    let query = deezer.extract("https://www.deezer.com/playlist/404").await;
+    assert_eq!(
+        query.err(),
+        Some(ParrotError::Message(DEEZER_PLAYLIST_FAILED))
+    );
+
+    let query = deezer.extract("https://www.deezer.com/en/artist/27").await;
+    assert_eq!(
+        query.err(),
+        Some(ParrotError::Message(DEEZER_INVALID_QUERY))
+    );
+}
+
+#[tokio::test]
+async fn test_apple_music_extract() {
+    let url = mock_server(&[
+        ("/lookup?id=1440857786", r#"{"resultCount":1,"results":[{"wrapperType":"track","artistName":"Queen","trackName":"Bohemian Rhapsody"}]}"#),
+        ("/lookup?id=1440650428", r#"{"resultCount":3,"results":[{"wrapperType":"collection","artistName":"Queen"},{"wrapperType":"track","artistName":"Queen","trackName":"Death on Two Legs"},{"wrapperType":"track","artistName":"Queen","trackName":"Lazing on a Sunday Afternoon"}]}"#),
+        ("/v1/catalog/us/playlists/pl.123", r#"{"data":[{"relationships":{"tracks":{"data":[{"attributes":{"name":"Song","artistName":"Artist"}}]}}}]}"#),
+    ])
+    .await;
+
+    let apple_music = AppleMusic::new(url.clone(), url.clone(), None);
+
+    let query = apple_music
+        .extract("https://music.apple.com/us/album/a-night-at-the-opera/1440650428?i=1440857786")
+        .await;
+    assert!(matches!(query, Ok(QueryType::Keywords(q)) if q == "Queen - Bohemian Rhapsody"));
+
+    let query = apple_music
+        .extract("https://music.apple.com/us/album/a-night-at-the-opera/1440650428")
+        .await;
+    assert!(matches!(query, Ok(QueryType::KeywordList(list)) if list.len() == 2));
+
+    let query = apple_music
+        .extract("https://music.apple.com/us/playlist/mix/pl.123")
+        .await;
+    assert_eq!(
+        query.err(),
+        Some(ParrotError::Message(APPLE_MUSIC_AUTH_FAILED))
+    );
+
+    let apple_music = AppleMusic::new(url.clone(), url, Some("token".to_string()));
+
+    let query = apple_music
+        .extract("https://music.apple.com/us/playlist/mix/pl.123")
+        .await;
+    assert!(matches!(query, Ok(QueryType::KeywordList(list)) if list == ["Artist - Song"]));
+}
+
+#[tokio::test]
+async fn test_tidal_extract() {
+    let url = mock_server(&[
+        ("/v1/tracks/77646168", r#"{"title":"Get Lucky","artists":[{"name":"Daft Punk"},{"name":"Pharrell Williams"}]}"#),
+        ("/v1/playlists/0b5df380-47d3-48fe-ae66-8f0dba90b1ee/tracks", r#"{"items":[{"title":"Get Lucky","artists":[{"name":"Daft Punk"}]}]}"#),
+    ])
+    .await;
+
+    let tidal = Tidal::new(url.clone(), None, "US".to_string());
+
+    let query = tidal
+        .extract("https://tidal.com/browse/track/77646168")
+        .await;
+    assert_eq!(query.err(), Some(ParrotError::Message(TIDAL_AUTH_FAILED)));
+
+    let tidal = Tidal::new(url, Some("token".to_string()), "US".to_string());
+
+    let query = tidal
+        .extract("https://tidal.com/browse/track/77646168")
+        .await;
+    assert!(
+        matches!(query, Ok(QueryType::Keywords(q)) if q == "Daft Punk Pharrell Williams - Get Lucky")
+    );
+
+    let query = tidal
+        .extract("https://listen.tidal.com/playlist/0b5df380-47d3-48fe-ae66-8f0dba90b1ee")
+        .await;
+    assert!(matches!(query, Ok(QueryType::KeywordList(list)) if list == ["Daft Punk - Get Lucky"]));
+}
+
+#[test]
+fn test_metadata_cache_key() {
+    assert_eq!(
+        cache_key("ytsearch:Rick Astley  -   Never Gonna Give You Up"),
+        cache_key("ytsearch:rick astley - never gonna give you up ")
+    );
+    assert_ne!(
+        cache_key("ytsearch:https://youtu.be/dQw4w9WgXcQ"),
+        cache_key("https://youtu.be/dQw4w9WgXcQ")
+    );
+}
+
+#[test]
+fn test_metadata_cache_drops_outdated_entries() {
+    let path = env::temp_dir().join(format!("parrot-metadata-{}.json", std::process::id()));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    // entries cached before the content filter have no age limit or categories
+    let contents = json!({
+        "url:https://youtu.be/old": { "title": "Old", "cached_at": now },
+        "url:https://youtu.be/new": { "title": "New", "cached_at": now, "age_limit": 18, "format": 1 },
+    });
+    fs::write(&path, contents.to_string()).unwrap();
+
+    let cache = MetadataCache::new(Some(path.clone()), Duration::from_secs(60), 10);
+    fs::remove_file(&path).unwrap();
+
+    assert!(cache.get("https://youtu.be/old").is_none());
+    let cached = cache.get("https://youtu.be/new").unwrap();
+    assert_eq!(cached.age_limit, Some(18));
+}
+
+#[test]
+fn test_metadata_cache_max_entries() {
+    let path = env::temp_dir().join(format!("parrot-metadata-max-{}.json", std::process::id()));
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let contents = json!({
+        "url:https://youtu.be/a": { "cached_at": now - 30, "format": 1 },
+        "url:https://youtu.be/b": { "cached_at": now - 20, "format": 1 },
+        "url:https://youtu.be/c": { "cached_at": now - 10, "format": 1 },
+        "url:https://youtu.be/expired": { "cached_at": now - 120, "format": 1 },
+    });
+    fs::write(&path, contents.to_string()).unwrap();
+
+    // the oldest entries past the limit are dropped on load
+    let cache = MetadataCache::new(Some(path.clone()), Duration::from_secs(60), 2);
+    fs::remove_file(&path).unwrap();
+
+    assert!(cache.get("https://youtu.be/expired").is_none());
+    assert!(cache.get("https://youtu.be/a").is_none());
+    assert!(cache.get("https://youtu.be/b").is_some());
+    assert!(cache.get("https://youtu.be/c").is_some());
+
+    // and as new ones come in
+    let cache: &'static MetadataCache = Box::leak(Box::new(MetadataCache::new(
+        None,
+        Duration::from_secs(60),
+        2,
+    )));
+    let metadata = |title: &str| CachedMetadata::from_ytdl_output(json!({ "title": title }));
+
+    cache.insert("https://youtu.be/d", &metadata("D"));
+    cache.insert("https://youtu.be/e", &metadata("E"));
+    cache.insert("https://youtu.be/f", &metadata("F"));
+
+    let cached = ["d", "e", "f"]
+        .iter()
+        .filter(|id| cache.get(&format!("https://youtu.be/{id}")).is_some())
+        .count();
+    assert_eq!(cached, 2);
+    assert!(cache.get("https://youtu.be/f").is_some());
+}
+
+#[test]
+fn test_audio_cache_key() {
+    assert_eq!(
+        audio_cache::cache_key("https://youtu.be/dQw4w9WgXcQ"),
+        "61e610a9d7fd37bc9df752aa7dd374f0852a4ab0d59bd3cad47afa5cb604fd01"
+    );
+}
+
+#[test]
+fn test_audio_cache_eviction() {
+    let dir = env::temp_dir().join(format!("parrot-audio-{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    let cache = AudioCache::new(&dir, 3000, 0).unwrap();
+
+    // stands in for a finished download of the given size
+    let download = |url: &str, size: usize| {
+        let key = audio_cache::cache_key(url);
+        fs::write(dir.join(format!("{key}.audio")), vec![0; size]).unwrap();
+        fs::write(dir.join(format!("{key}.json")), "{}").unwrap();
+        cache.insert(&key, size as u64);
+    };
+
+    download("https://youtu.be/a", 1000);
+    download("https://youtu.be/b", 1000);
+    download("https://youtu.be/c", 1000);
+    assert_eq!(cache.size(), 3000);
+
+    // downloading a track again replaces it rather than counting it twice
+    download("https://youtu.be/c", 1000);
+    assert_eq!(cache.size(), 3000);
+
+    // playing the oldest track makes the next oldest the first to go
+    assert!(cache.get("https://youtu.be/a").is_some());
+    download("https://youtu.be/d", 1000);
+
+    assert_eq!(cache.size(), 3000);
+    assert!(cache.get("https://youtu.be/b").is_none());
+    let key = audio_cache::cache_key("https://youtu.be/b");
+    assert!(!dir.join(format!("{key}.audio")).exists());
+
+    // a track bigger than what's left pushes out as many as it takes
+    download("https://youtu.be/e", 2500);
+    assert_eq!(cache.size(), 2500);
+    assert!(cache.get("https://youtu.be/e").is_some());
+    for url in [
+        "https://youtu.be/a",
+        "https://youtu.be/c",
+        "https://youtu.be/d",
+    ] {
+        assert!(cache.get(url).is_none());
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn ogg_page(lacing: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut page = b"OggS".to_vec();
+    page.extend([0u8; 22]);
+    page.push(lacing.len() as u8);
+    page.extend(lacing);
+    page.extend(data);
+    page
+}
+
+#[test]
+fn test_ogg_opus_reader() {
+    let first = vec![1u8; 300];
+    let second = vec![2u8; 265];
+
+    let mut stream = ogg_page(&[19], b"OpusHead\x01\x02\x38\x01\x80\xbb\0\0\0\0\0");
+    stream.extend(ogg_page(
+        &[255, 45, 255],
+        &[&first[..], &second[..255]].concat(),
+    ));
+    stream.extend(ogg_page(&[10], &second[255..]));
+
+    let mut output = vec![];
+    OggOpusReader::new(&stream[..])
+        .read_to_end(&mut output)
+        .unwrap();
+
+    let mut expected = 300i16.to_le_bytes().to_vec();
+    expected.extend(&first);
+    expected.extend(265i16.to_le_bytes());
+    expected.extend(&second);
+
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,53 @@
+use crate::guild::theme::{
+    current_theme, format_color, parse_color, render_template, with_theme, Theme,
+};
+
+#[test]
+fn test_parse_color() {
+    assert_eq!(parse_color("#5865F2"), Some(0x5865F2));
+    assert_eq!(parse_color(" 5865f2 "), Some(0x5865F2));
+    assert_eq!(parse_color("#FFF"), None);
+    assert_eq!(parse_color("#GGGGGG"), None);
+    assert_eq!(parse_color("+123456"), None);
+    assert_eq!(parse_color(""), None);
+
+    assert_eq!(format_color(0x5865F2), "#5865F2");
+    assert_eq!(format_color(0xFF), "#0000FF");
+}
+
+#[test]
+fn test_render_template() {
+    let values = [("title", "Song"), ("url", "https://a.com")];
+
+    assert_eq!(
+        render_template("[{title}]({url})", &values),
+        "[Song](https://a.com)"
+    );
+    assert_eq!(render_template("{title} {eta} {", &values), "Song {eta} {");
+    assert_eq!(render_template("{{title}}", &values), "{Song}");
+
+    // values are filled in as they are
+    let values = [("title", "{url}"), ("url", "https://a.com")];
+    assert_eq!(render_template("{title}", &values), "{url}");
+}
+
+#[test]
+fn test_theme_defaults() {
+    let theme: Theme = serde_json::from_str("{}").unwrap();
+    assert_eq!(theme, Theme::default());
+    assert!(theme.thumbnails);
+    assert!(!theme.compact);
+    assert_eq!(theme.accent_color, None);
+}
+
+#[tokio::test]
+async fn test_current_theme() {
+    assert_eq!(current_theme(), Theme::default());
+
+    let theme = Theme {
+        accent_color: Some(0x5865F2),
+        ..Theme::default()
+    };
+    let current = with_theme(theme.clone(), async { current_theme() }).await;
+    assert_eq!(current, theme);
+}
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::utils::get_human_readable_timestamp;
+use crate::utils::{get_human_readable_timestamp, truncate};
 
 #[test]
 fn test_get_human_readable_timestamp() {
@@ -22,3 +22,11 @@ fn test_get_human_readable_timestamp() {
     let result = get_human_readable_timestamp(None);
     assert_eq!(result, "∞");
 }
+
+#[test]
+fn test_truncate() {
+    assert_eq!(truncate("short", 10), "short");
+    assert_eq!(truncate("exactly10!", 10), "exactly10!");
+    assert_eq!(truncate("a bit too long", 10), "a bit too…");
+    assert_eq!(truncate("ééééé", 3), "éé…");
+}
@@ -2,12 +2,14 @@ use std::{sync::Arc, time::Duration};
 
 use serenity::{
     builder::CreateEmbed,
+    client::Context,
     http::{Http, HttpError},
     model::{
         application::interaction::{
             application_command::ApplicationCommandInteraction, InteractionResponseType,
         },
         channel::Message,
+        id::GuildId,
     },
     Error,
 };
@@ -16,9 +18,30 @@ use url::Url;
 
 use crate::{
     errors::ParrotError,
+    guild::{
+        settings::GuildSettingsMap,
+        theme::{current_theme, render_template, Theme},
+        visibility::ResponseKind,
+    },
     messaging::message::{ParrotMessage, ParrotMusicMessage},
+    sources::provider::Provider,
 };
 
+/// Longest value Discord accepts for an embed field.
+pub const EMBED_FIELD_MAX_LEN: usize = 1024;
+
+/// Longest description Discord accepts for an embed.
+pub const EMBED_DESCRIPTION_MAX_LEN: usize = 4096;
+
+/// Whether responses of the kind are only shown to whoever ran the command, which they are
+/// unless the guild made them public.
+pub async fn is_ephemeral(ctx: &Context, guild_id: Option<GuildId>, kind: ResponseKind) -> bool {
+    let data = ctx.data.read().await;
+    !guild_id
+        .and_then(|guild_id| data.get::<GuildSettingsMap>()?.get(&guild_id))
+        .map_or(false, |settings| settings.is_response_public(kind))
+}
+
 pub async fn create_response(
     http: &Arc<Http>,
     interaction: &mut ApplicationCommandInteraction,
@@ -47,6 +70,32 @@ pub async fn create_response_text(
     create_embed_response(http, interaction, embed).await
 }
 
+/// Like [`create_response_music`], only showing it to the user who ran the command when
+/// `ephemeral`.
+pub async fn create_response_music_ephemeral(
+    http: &Arc<Http>,
+    interaction: &mut ApplicationCommandInteraction,
+    message: ParrotMusicMessage,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let mut embed = CreateEmbed::default();
+    embed.description(format!("{}", ParrotMessage::Music(message)));
+    create_embed_response_ephemeral(http, interaction, embed, ephemeral).await
+}
+
+/// Like [`create_response_text`], only showing it to the user who ran the command when
+/// `ephemeral`.
+pub async fn create_response_ephemeral(
+    http: &Arc<Http>,
+    interaction: &mut ApplicationCommandInteraction,
+    content: &str,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    let mut embed = CreateEmbed::default();
+    embed.description(content);
+    create_embed_response_ephemeral(http, interaction, embed, ephemeral).await
+}
+
 pub async fn edit_response(
     http: &Arc<Http>,
     interaction: &mut ApplicationCommandInteraction,
@@ -80,11 +129,26 @@ pub async fn create_embed_response(
     interaction: &mut ApplicationCommandInteraction,
     embed: CreateEmbed,
 ) -> Result<(), ParrotError> {
+    create_embed_response_ephemeral(http, interaction, embed, false).await
+}
+
+/// Like [`create_embed_response`], only showing the embed to the user who ran the command
+/// when `ephemeral`. Responses that were already sent stay as they were.
+pub async fn create_embed_response_ephemeral(
+    http: &Arc<Http>,
+    interaction: &mut ApplicationCommandInteraction,
+    mut embed: CreateEmbed,
+    ephemeral: bool,
+) -> Result<(), ParrotError> {
+    apply_theme(&mut embed, &current_theme());
+
     match interaction
         .create_interaction_response(&http, |response| {
             response
                 .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|message| message.add_embed(embed.clone()))
+                .interaction_response_data(|message| {
+                    message.add_embed(embed.clone()).ephemeral(ephemeral)
+                })
         })
         .await
         .map_err(Into::into)
@@ -108,8 +172,10 @@ pub async fn create_embed_response(
 pub async fn edit_embed_response(
     http: &Arc<Http>,
     interaction: &mut ApplicationCommandInteraction,
-    embed: CreateEmbed,
+    mut embed: CreateEmbed,
 ) -> Result<Message, ParrotError> {
+    apply_theme(&mut embed, &current_theme());
+
     interaction
         .edit_original_interaction_response(&http, |message| message.content(" ").add_embed(embed))
         .await
@@ -117,33 +183,60 @@ pub async fn edit_embed_response(
 }
 
 pub async fn create_now_playing_embed(track: &TrackHandle) -> CreateEmbed {
+    let theme = current_theme();
     let mut embed = CreateEmbed::default();
     let metadata = track.metadata().clone();
 
     embed.author(|author| author.name(ParrotMusicMessage::NowPlaying));
-    embed.title(metadata.title.unwrap());
-    embed.url(metadata.source_url.as_ref().unwrap());
+
+    let title = metadata.title.unwrap();
+    let source_url = metadata.source_url.unwrap();
+    let channel = metadata.channel.unwrap_or_else(|| String::from("N/A"));
 
     let position = get_human_readable_timestamp(Some(track.get_info().await.unwrap().position));
     let duration = get_human_readable_timestamp(metadata.duration);
 
-    embed.field("Progress", format!(">>> {} / {}", position, duration), true);
-
-    match metadata.channel {
-        Some(channel) => embed.field("Channel", format!(">>> {}", channel), true),
-        None => embed.field("Channel", ">>> N/A", true),
+    // templates are bounded, but repeating placeholders can still make them grow past that
+    match &theme.now_playing_template {
+        Some(template) => embed.description(truncate(
+            &render_template(
+                template,
+                &[
+                    ("title", &title),
+                    ("url", &source_url),
+                    ("channel", &channel),
+                    ("position", &position),
+                    ("duration", &duration),
+                ],
+            ),
+            EMBED_DESCRIPTION_MAX_LEN,
+        )),
+        None => embed.title(&title).url(&source_url),
     };
 
-    embed.thumbnail(&metadata.thumbnail.unwrap());
+    if !theme.compact {
+        embed.field("Progress", format!(">>> {} / {}", position, duration), true);
+        embed.field("Channel", format!(">>> {}", channel), true);
 
-    let source_url = metadata.source_url.as_ref().unwrap();
+        let (footer_text, footer_icon_url) = get_footer_info(&source_url);
+        embed.footer(|f| f.text(footer_text).icon_url(footer_icon_url));
+    }
 
-    let (footer_text, footer_icon_url) = get_footer_info(source_url);
-    embed.footer(|f| f.text(footer_text).icon_url(footer_icon_url));
+    if theme.thumbnails {
+        embed.thumbnail(&metadata.thumbnail.unwrap());
+    }
 
+    apply_theme(&mut embed, &theme);
     embed
 }
 
+/// Styles an embed after the guild's theme, for what the embed itself doesn't decide.
+pub fn apply_theme(embed: &mut CreateEmbed, theme: &Theme) {
+    if let Some(color) = theme.accent_color {
+        embed.color(color);
+    }
+}
+
 pub fn get_footer_info(url: &str) -> (String, String) {
     let url_data = Url::parse(url).unwrap();
     let domain = url_data.host_str().unwrap();
@@ -151,12 +244,30 @@ pub fn get_footer_info(url: &str) -> (String, String) {
     // remove www prefix because it looks ugly
     let domain = domain.replace("www.", "");
 
+    // known services get their proper name and icon, even when streamed from a mirror
+    let provider = Provider::from_url(&url_data);
+    let name = provider.name().unwrap_or(&domain);
+    let icon_domain = provider.icon_domain().unwrap_or(&domain);
+
     (
-        format!("Streaming via {}", domain),
-        format!("https://www.google.com/s2/favicons?domain={}", domain),
+        format!("Streaming via {}", name),
+        format!("https://www.google.com/s2/favicons?domain={}", icon_domain),
     )
 }
 
+/// Cuts text down to at most `max_len` characters, ending it with an ellipsis when it
+/// didn't fit.
+pub fn truncate(text: &str, max_len: usize) -> String {
+    match text.chars().count() > max_len {
+        true => {
+            let mut truncated: String = text.chars().take(max_len.saturating_sub(1)).collect();
+            truncated.push('…');
+            truncated
+        }
+        false => text.to_string(),
+    }
+}
+
 pub fn get_human_readable_timestamp(duration: Option<Duration>) -> String {
     match duration {
         Some(duration) if duration == Duration::MAX => "∞".to_string(),
@@ -175,6 +286,22 @@ pub fn get_human_readable_timestamp(duration: Option<Duration>) -> String {
     }
 }
 
-pub fn compare_domains(domain: &str, subdomain: &str) -> bool {
-    subdomain == domain || subdomain.ends_with(domain)
+/// Parses a `HH:MM:SS`, `MM:SS` or plain seconds timestamp.
+pub fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let units = timestamp
+        .trim()
+        .split(':')
+        .map(|unit| unit.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    let seconds = match units.as_slice() {
+        [seconds] => *seconds,
+        [minutes, seconds] if *seconds < 60 => minutes.checked_mul(60)?.checked_add(*seconds)?,
+        [hours, minutes, seconds] if *minutes < 60 && *seconds < 60 => hours
+            .checked_mul(3600)?
+            .checked_add(minutes * 60 + seconds)?,
+        _ => return None,
+    };
+
+    Some(Duration::from_secs(seconds))
 }